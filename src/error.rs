@@ -1,4 +1,7 @@
-use cosmwasm_std::{Response, StdError};
+use cosmwasm_std::{
+    Coin, ConversionOverflowError, DivideByZeroError, Empty, OverflowError, Response, StdError,
+    Uint128,
+};
 use cw20_base::ContractError as Cw20ContractError;
 use cw_utils::ParseReplyError;
 use thiserror::Error;
@@ -21,6 +24,91 @@ pub enum CwTokenError {
     /// CW20 Contract
     #[error("{0}")]
     Cw20ContractError(#[from] Cw20ContractError),
+
+    /// Fewer tokens than expected were received
+    #[error("insufficient funds: expected {expected}, received {received}")]
+    InsufficientFunds {
+        /// The expected amount of tokens
+        expected: Coin,
+        /// The amount of tokens actually received
+        received: Coin,
+    },
+
+    /// Funds other than the expected tokens were received
+    #[error("unexpected funds: expected only {expected}, received {received:?}")]
+    UnexpectedFunds {
+        /// The expected amount of tokens
+        expected: Coin,
+        /// All funds that were received
+        received: Vec<Coin>,
+    },
+
+    /// The denom does not follow the format required by the implementation
+    #[error("invalid denom: {denom}")]
+    InvalidDenom {
+        /// The invalid denom
+        denom: String,
+    },
+
+    /// The denom created by the chain is not the expected denom
+    #[error("denom mismatch: expected {expected}, created {actual}")]
+    DenomMismatch {
+        /// The expected denom
+        expected: String,
+        /// The denom that was actually created
+        actual: String,
+    },
+
+    /// The amount is zero
+    #[error("amount must be greater than zero")]
+    ZeroAmount {},
+
+    /// The first mint does not exceed the dead shares of the inflation
+    /// protection
+    #[error("first mint must be larger than {dead_shares} dead shares")]
+    FirstMintTooSmall {
+        /// The amount of dead shares
+        dead_shares: Uint128,
+    },
+
+    /// The inflation protection is configured with zero dead shares
+    #[error("dead shares amount can't be zero")]
+    ZeroDeadShares {},
+
+    /// Historical queries were made but snapshots are not enabled
+    #[error("snapshots are not enabled")]
+    SnapshotsDisabled {},
+
+    /// The caller is not allowed to perform the action
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    /// The contract is not the admin of the token factory denom
+    #[error(
+        "{address} is not the admin of {denom}, the admin is {}",
+        .admin.as_deref().unwrap_or("none")
+    )]
+    NotDenomAdmin {
+        /// The token factory denom
+        denom: String,
+        /// The address that attempted to act as admin
+        address: String,
+        /// The current admin of the denom, or `None` if the denom has no
+        /// admin
+        admin: Option<String>,
+    },
+
+    /// Arithmetic overflow
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    /// Overflow when converting between integer types
+    #[error("{0}")]
+    ConversionOverflow(#[from] ConversionOverflowError),
+
+    /// Division by zero
+    #[error("{0}")]
+    DivideByZero(#[from] DivideByZeroError),
 }
 
 impl From<CwTokenError> for StdError {
@@ -32,5 +120,5 @@ impl From<CwTokenError> for StdError {
 /// CW token Result type
 pub type CwTokenResult<T> = Result<T, CwTokenError>;
 
-/// CW Token Response type
-pub type CwTokenResponse = CwTokenResult<Response>;
+/// CW Token Response type, with custom messages of type `C`
+pub type CwTokenResponse<C = Empty> = CwTokenResult<Response<C>>;
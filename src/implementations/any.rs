@@ -0,0 +1,276 @@
+use std::fmt::Display;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Addr, Binary, Coin, CustomMsg, CustomQuery, Deps, DepsMut, Env, MessageInfo, Uint128,
+};
+
+use crate::cw4626::Cw4626;
+use crate::osmosis::OsmosisDenom;
+use crate::{
+    Burn, BurnFrom, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate, Mint, Query,
+    Receive, Transfer,
+};
+
+#[cw_serde]
+/// Either a [`Cw4626`] or an [`OsmosisDenom`] vault token. This allows the
+/// implementation to be chosen at runtime, e.g. in the `instantiate` entry
+/// point of a contract, and saved in an `Item`. All trait methods delegate to
+/// the wrapped implementation.
+///
+/// Only the implementations that work with any custom message type are
+/// included. [`crate::neutron::NeutronDenom`] and
+/// [`crate::kujira::KujiraDenom`] require the chain specific custom message,
+/// so they can't be wrapped in the same enum as tokens for other chains.
+///
+/// ## Example
+/// ```ignore
+/// pub const VAULT_TOKEN: Item<AnyVaultToken> = Item::new("vault_token");
+///
+/// pub fn instantiate(
+///     deps: DepsMut,
+///     env: Env,
+///     info: MessageInfo,
+///     msg: InstantiateMsg,
+/// ) -> Result<Response, ContractError> {
+///     let vault_token = match msg.native {
+///         true => OsmosisDenom::new(env.contract.address.to_string(), msg.subdenom).into(),
+///         false => Cw4626::new(&env).into(),
+///     };
+///     VAULT_TOKEN.save(deps.storage, &vault_token)?;
+///     vault_token.instantiate(deps, msg.init_info)
+/// }
+/// ```
+pub enum AnyVaultToken {
+    /// A [`Cw4626`] tokenized vault
+    Cw4626(Cw4626),
+    /// An [`OsmosisDenom`] native token
+    OsmosisDenom(OsmosisDenom),
+}
+
+impl From<Cw4626> for AnyVaultToken {
+    fn from(token: Cw4626) -> Self {
+        Self::Cw4626(token)
+    }
+}
+
+impl From<OsmosisDenom> for AnyVaultToken {
+    fn from(token: OsmosisDenom) -> Self {
+        Self::OsmosisDenom(token)
+    }
+}
+
+impl Display for AnyVaultToken {
+    /// Returns the string representation of the wrapped implementation.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cw4626(token) => token.fmt(f),
+            Self::OsmosisDenom(token) => token.fmt(f),
+        }
+    }
+}
+
+impl<Q: CustomQuery> Query<Q> for AnyVaultToken {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
+        match self {
+            Self::Cw4626(token) => token.query_balance(deps, address),
+            Self::OsmosisDenom(token) => token.query_balance(deps, address),
+        }
+    }
+
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        match self {
+            Self::Cw4626(token) => token.query_total_supply(deps),
+            Self::OsmosisDenom(token) => token.query_total_supply(deps),
+        }
+    }
+
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        match self {
+            Self::Cw4626(token) => token.query_inflation_protection(deps),
+            Self::OsmosisDenom(token) => token.query_inflation_protection(deps),
+        }
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Instantiate<C, Q> for AnyVaultToken {
+    /// Since the implementation is only known at runtime, the init info is
+    /// the binary encoded [`Instantiate::InitInfo`] of the wrapped
+    /// implementation.
+    type InitInfo = Option<Binary>;
+
+    fn instantiate(&self, deps: DepsMut<Q>, init_info: Option<Binary>) -> CwTokenResponse<C> {
+        self.instantiate_binary(deps, init_info)
+    }
+
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        match self {
+            Self::Cw4626(token) => token.instantiate_binary(deps, init_info),
+            Self::OsmosisDenom(token) => token.instantiate_binary(deps, init_info),
+        }
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Mint<C, Q> for AnyVaultToken {
+    fn mint(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        match self {
+            Self::Cw4626(token) => token.mint(deps, env, recipient, amount),
+            Self::OsmosisDenom(token) => token.mint(deps, env, recipient, amount),
+        }
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Burn<C, Q> for AnyVaultToken {
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        match self {
+            Self::Cw4626(token) => token.burn(deps, env, amount),
+            Self::OsmosisDenom(token) => token.burn(deps, env, amount),
+        }
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> BurnFrom<C, Q> for AnyVaultToken {
+    fn burn_from(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        owner: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        match self {
+            Self::Cw4626(token) => token.burn_from(deps, env, owner, amount),
+            Self::OsmosisDenom(token) => token.burn_from(deps, env, owner, amount),
+        }
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for AnyVaultToken {
+    fn transfer(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        match self {
+            Self::Cw4626(token) => token.transfer(deps, env, recipient, amount),
+            Self::OsmosisDenom(token) => token.transfer(deps, env, recipient, amount),
+        }
+    }
+}
+
+impl<Q: CustomQuery> Receive<Q> for AnyVaultToken {
+    fn receive(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        info: &MessageInfo,
+        amount: Uint128,
+    ) -> CwTokenResult<Vec<Coin>> {
+        match self {
+            Self::Cw4626(token) => token.receive(deps, env, info, amount),
+            Self::OsmosisDenom(token) => token.receive(deps, env, info, amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{from_binary, to_binary, Response};
+    use cw_storage_plus::Item;
+
+    use crate::cw4626::Cw4626InstantiateMsg;
+
+    use super::*;
+
+    const VAULT_TOKEN: Item<AnyVaultToken> = Item::new("vault_token");
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let env = mock_env();
+        let tokens: Vec<AnyVaultToken> = vec![
+            Cw4626::new(&env).into(),
+            OsmosisDenom::new("owner".to_string(), "subdenom".to_string()).into(),
+        ];
+
+        for token in tokens {
+            let deserialized: AnyVaultToken = from_binary(&to_binary(&token).unwrap()).unwrap();
+            assert_eq!(deserialized, token);
+        }
+    }
+
+    #[test]
+    fn test_to_string() {
+        let env = mock_env();
+
+        let token: AnyVaultToken = Cw4626::new(&env).into();
+        assert_eq!(token.to_string(), env.contract.address.to_string());
+
+        let token: AnyVaultToken =
+            OsmosisDenom::new("owner".to_string(), "subdenom".to_string()).into();
+        assert_eq!(token.to_string(), "factory/owner/subdenom");
+    }
+
+    #[test]
+    fn test_delegates_to_variant() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let recipient = Addr::unchecked("recipient");
+
+        // Save and load the token as it would be done in a contract
+        let token: AnyVaultToken = Cw4626::new(&env).into();
+        VAULT_TOKEN.save(deps.as_mut().storage, &token).unwrap();
+        let token = VAULT_TOKEN.load(deps.as_ref().storage).unwrap();
+
+        let init_info = to_binary(&Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        })
+        .unwrap();
+        let _: Response = token.instantiate(deps.as_mut(), Some(init_info)).unwrap();
+
+        // Mint to the contract, then transfer and burn
+        let _: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        let _: Response = token
+            .transfer(deps.as_mut(), &env, &recipient, Uint128::new(300))
+            .unwrap();
+        let _: Response = token.burn(deps.as_mut(), &env, Uint128::new(200)).unwrap();
+
+        assert_eq!(
+            token.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::new(800)
+        );
+        assert_eq!(
+            token
+                .query_balance(deps.as_ref(), env.contract.address.to_string())
+                .unwrap(),
+            Uint128::new(500)
+        );
+        assert_eq!(
+            token.query_balance(deps.as_ref(), &recipient).unwrap(),
+            Uint128::new(300)
+        );
+    }
+}
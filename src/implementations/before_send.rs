@@ -0,0 +1,162 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin};
+
+/// The address of the Osmosis TokenFactory module account. Tokens are minted
+/// to and burned from this account, so it appears as the sender of mints and
+/// the recipient of burns in before send hooks.
+pub const OSMOSIS_TOKENFACTORY_MODULE_ADDRESS: &str = "osmo19ejy8n9qsectrf4semdp9cpknflld0j64mwamn";
+
+#[cw_serde]
+/// A transfer of a token factory denom, as passed to its before send hook.
+pub struct BeforeSendMsg {
+    /// The sender of the tokens
+    pub from: String,
+    /// The recipient of the tokens
+    pub to: String,
+    /// The transferred tokens
+    pub amount: Coin,
+}
+
+#[cw_serde]
+/// The sudo messages sent by the Osmosis TokenFactory module to the before
+/// send hook contract of a denom, set with
+/// [`crate::osmosis::OsmosisDenom::set_before_send_hook`]. A contract
+/// handling the hook should include these variants in its `SudoMsg`, or
+/// deserialize them directly in its `sudo` entry point.
+///
+/// ## Example
+/// ```ignore
+/// #[entry_point]
+/// pub fn sudo(deps: DepsMut, env: Env, msg: BeforeSendSudoMsg) -> StdResult<Response> {
+///     match msg.transfer_event(OSMOSIS_TOKENFACTORY_MODULE_ADDRESS) {
+///         TransferEvent::Mint { to, amount } => ..,
+///         TransferEvent::Burn { from, amount } => ..,
+///         TransferEvent::Transfer { from, to, amount } => ..,
+///     }
+/// }
+/// ```
+pub enum BeforeSendSudoMsg {
+    /// Called before each transfer of the denom. Returning an error from the
+    /// `sudo` entry point blocks the transfer.
+    BlockBeforeSend(BeforeSendMsg),
+    /// Called before each transfer of the denom. Errors are ignored and can't
+    /// block the transfer, so this is only useful for tracking.
+    TrackBeforeSend(BeforeSendMsg),
+}
+
+#[cw_serde]
+/// A typed transfer of a token factory denom, parsed from a
+/// [`BeforeSendSudoMsg`]. Mints and burns are recognized by the token factory
+/// module account being the sender or recipient.
+pub enum TransferEvent {
+    /// Tokens were minted to `to`
+    Mint {
+        /// The recipient of the minted tokens
+        to: Addr,
+        /// The minted tokens
+        amount: Coin,
+    },
+    /// Tokens were burned from `from`
+    Burn {
+        /// The address the tokens were burned from
+        from: Addr,
+        /// The burned tokens
+        amount: Coin,
+    },
+    /// Tokens were transferred from `from` to `to`
+    Transfer {
+        /// The sender of the tokens
+        from: Addr,
+        /// The recipient of the tokens
+        to: Addr,
+        /// The transferred tokens
+        amount: Coin,
+    },
+}
+
+impl BeforeSendSudoMsg {
+    /// Returns the [`BeforeSendMsg`] of the hook.
+    pub const fn msg(&self) -> &BeforeSendMsg {
+        match self {
+            Self::BlockBeforeSend(msg) | Self::TrackBeforeSend(msg) => msg,
+        }
+    }
+
+    /// Returns whether the hook can block the transfer by returning an error.
+    pub const fn is_blocking(&self) -> bool {
+        matches!(self, Self::BlockBeforeSend(_))
+    }
+
+    /// ## Description
+    /// Parses the hook into a [`TransferEvent`]. `module_address` is the
+    /// address of the token factory module account, which is
+    /// [`OSMOSIS_TOKENFACTORY_MODULE_ADDRESS`] on Osmosis.
+    pub fn transfer_event(&self, module_address: &str) -> TransferEvent {
+        let BeforeSendMsg { from, to, amount } = self.msg().clone();
+        if from == module_address {
+            TransferEvent::Mint {
+                to: Addr::unchecked(to),
+                amount,
+            }
+        } else if to == module_address {
+            TransferEvent::Burn {
+                from: Addr::unchecked(from),
+                amount,
+            }
+        } else {
+            TransferEvent::Transfer {
+                from: Addr::unchecked(from),
+                to: Addr::unchecked(to),
+                amount,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::from_slice;
+    use test_case::test_case;
+
+    use super::*;
+
+    const MODULE: &str = OSMOSIS_TOKENFACTORY_MODULE_ADDRESS;
+
+    fn sudo_msg(from: &str, to: &str) -> BeforeSendSudoMsg {
+        BeforeSendSudoMsg::TrackBeforeSend(BeforeSendMsg {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount: Coin::new(1000, "factory/vault/shares"),
+        })
+    }
+
+    #[test]
+    fn deserializes_osmosis_sudo_msgs() {
+        let msg: BeforeSendSudoMsg = from_slice(
+            br#"{"block_before_send":{"from":"alice","to":"bob","amount":{"denom":"factory/vault/shares","amount":"1000"}}}"#,
+        )
+        .unwrap();
+        assert!(msg.is_blocking());
+        assert_eq!(
+            msg.msg(),
+            &BeforeSendMsg {
+                from: "alice".to_string(),
+                to: "bob".to_string(),
+                amount: Coin::new(1000, "factory/vault/shares"),
+            }
+        );
+
+        let msg: BeforeSendSudoMsg = from_slice(
+            br#"{"track_before_send":{"from":"alice","to":"bob","amount":{"denom":"factory/vault/shares","amount":"1000"}}}"#,
+        )
+        .unwrap();
+        assert!(!msg.is_blocking());
+    }
+
+    #[test_case(MODULE, "bob" => TransferEvent::Mint { to: Addr::unchecked("bob"), amount: Coin::new(1000, "factory/vault/shares") } ; "mint")]
+    #[test_case("alice", MODULE => TransferEvent::Burn { from: Addr::unchecked("alice"), amount: Coin::new(1000, "factory/vault/shares") } ; "burn")]
+    #[test_case("alice", "bob" => TransferEvent::Transfer { from: Addr::unchecked("alice"), to: Addr::unchecked("bob"), amount: Coin::new(1000, "factory/vault/shares") } ; "transfer")]
+    fn transfer_event(from: &str, to: &str) -> TransferEvent {
+        sudo_msg(from, to).transfer_event(MODULE)
+    }
+}
@@ -1,17 +1,52 @@
 use std::fmt::Display;
 
 use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_std::{
-    attr, from_binary, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, Response, StdError,
-    StdResult, Uint128,
+    attr, from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, CustomMsg, CustomQuery, Deps,
+    DepsMut, Env, Event, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Storage,
+    SubMsg, Uint128,
 };
-use cw20::MarketingInfoResponse;
-use cw20_base::contract::query_balance;
-use cw20_base::msg::{InstantiateMarketingInfo, InstantiateMsg};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MarketingInfoResponse};
+use cw20_base::allowances::{
+    deduct_allowance, execute_burn_from, execute_decrease_allowance, execute_increase_allowance,
+    execute_send_from, execute_transfer_from, query_allowance,
+};
+use cw20_base::contract::{
+    execute_burn, execute_send, execute_transfer, execute_update_marketing, execute_upload_logo,
+    query_balance, query_download_logo, query_marketing_info, query_minter, query_token_info,
+};
+use cw20_base::enumerable::{query_all_accounts, query_owner_allowances, query_spender_allowances};
+use cw20_base::msg::{InstantiateMarketingInfo, InstantiateMsg, QueryMsg};
 use cw20_base::state::{TokenInfo, BALANCES, MARKETING_INFO, TOKEN_INFO};
-use cw20_base::ContractError;
+use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
+
+use crate::inflation::take_dead_shares;
+use crate::{
+    Burn, BurnFrom, CwTokenError, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate,
+    Mint, Query, Receive, Transfer,
+};
 
-use crate::{Burn, CwTokenResponse, CwTokenResult, Instantiate, Mint, Receive, VaultToken};
+/// Whether balance and supply snapshots are recorded. See
+/// [`Cw4626InstantiateMsg::snapshots`].
+const SNAPSHOTS_ENABLED: Item<bool> = Item::new("cw_vault_token/cw4626/snapshots");
+
+/// Snapshots of the balances in `cw20_base`'s `BALANCES`.
+const BALANCE_SNAPSHOTS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "cw_vault_token/cw4626/balance",
+    "cw_vault_token/cw4626/balance__checkpoints",
+    "cw_vault_token/cw4626/balance__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Snapshots of the total supply in `cw20_base`'s `TOKEN_INFO`.
+const SUPPLY_SNAPSHOTS: SnapshotItem<Uint128> = SnapshotItem::new(
+    "cw_vault_token/cw4626/total_supply",
+    "cw_vault_token/cw4626/total_supply__checkpoints",
+    "cw_vault_token/cw4626/total_supply__changelog",
+    Strategy::EveryBlock,
+);
 
 #[cw_serde]
 /// Representation of a tokenized vault following the standard defined in
@@ -21,13 +56,22 @@ use crate::{Burn, CwTokenResponse, CwTokenResult, Instantiate, Mint, Receive, Va
 /// Minter extension, so only the `cw4626` contract itself can mint tokens.
 /// This implementation also does not support initial balances.
 ///
-/// To keep compatibility with OsmosisDenom `burn_from` is not implemented.
-/// This means that before tokens can be burned they must be transferred to
-/// the `cw4626` contract using [`Cw4626::receive`].
+/// Tokens can be burned from the `cw4626` contract's balance after they have
+/// been transferred to it using [`Cw4626::receive`] or a cw20 `Send` handled
+/// by [`Cw4626::receive_cw20`], or directly from an owner's balance using
+/// [`Cw4626::burn_from`], which spends the allowance the owner has given the
+/// `cw4626` contract.
 ///
-/// This struct implements the [`VaultToken`] trait.
+/// All storage of the token is kept under an optional prefix, see
+/// [`Cw4626::with_prefix`].
+///
+/// This struct implements the [`crate::VaultToken`] trait for any custom
+/// message and query type.
 pub struct Cw4626 {
     address: Addr,
+    /// Namespace of the token's storage. Empty for the `cw20_base` layout.
+    #[serde(default)]
+    prefix: String,
 }
 
 impl Cw4626 {
@@ -46,14 +90,445 @@ impl Cw4626 {
     ///     msg: InstantiateMsg,
     /// ) -> Result<Response, ContractError> {
     ///     let cw4626 = Cw4626::new(&env);
-    ///     cw4626.instantiate(deps, to_binary(&msg.init_info)?)
+    ///     cw4626.instantiate(deps, msg.init_info)
     /// }
     /// ```
     pub fn new(env: &Env) -> Self {
         Cw4626 {
             address: env.contract.address.clone(),
+            prefix: String::new(),
+        }
+    }
+
+    /// ## Description
+    /// Keeps all storage of the token in the namespace `prefix`, so that one
+    /// contract can hold several [`Cw4626`] tokens, e.g. one share token per
+    /// strategy. All trait methods and query helpers only read and write the
+    /// token's own namespace. Without a prefix, the token uses the same keys
+    /// as `cw20_base`.
+    ///
+    /// The namespace is `cw4626/{prefix}`, so that no prefix can collide with
+    /// the keys of `cw20_base` (e.g. `balance` or `token_info`) or of an
+    /// unprefixed token in the same contract.
+    ///
+    /// Note that cw20 messages and hooks are all sent to the same contract
+    /// address, so the contract must route them to the right token itself.
+    /// The events emitted by the trait methods carry a `prefix` attribute.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let conservative = Cw4626::new(&env).with_prefix("conservative");
+    /// let aggressive = Cw4626::new(&env).with_prefix("aggressive");
+    /// ```
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Returns the event emitted by `action`. Events of prefixed tokens carry
+    /// a `prefix` attribute, so that the events of several tokens in the same
+    /// contract can be told apart.
+    fn event(&self, action: &str) -> Event {
+        let event = Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
+            attr("action", action),
+            attr("vault_token_address", self.to_string()),
+        ]);
+        match self.prefix.is_empty() {
+            true => event,
+            false => event.add_attribute("prefix", &self.prefix),
+        }
+    }
+
+    /// The storage namespace of a prefixed token.
+    fn namespace(&self) -> Vec<u8> {
+        format!("cw4626/{}", self.prefix).into_bytes()
+    }
+
+    /// Runs `f` with `deps` scoped to the token's storage namespace. The
+    /// custom query type is dropped, since `cw20_base` only accepts `Empty`.
+    fn scoped<Q: CustomQuery, T>(&self, deps: DepsMut<Q>, f: impl FnOnce(DepsMut) -> T) -> T {
+        let querier = QuerierWrapper::new(&*deps.querier);
+        if self.prefix.is_empty() {
+            return f(DepsMut {
+                storage: deps.storage,
+                api: deps.api,
+                querier,
+            });
+        }
+        let mut storage = PrefixedStorage::new(deps.storage, &self.namespace());
+        f(DepsMut {
+            storage: &mut storage,
+            api: deps.api,
+            querier,
+        })
+    }
+
+    /// Runs `f` with `deps` scoped to the token's storage namespace. The
+    /// custom query type is dropped, since `cw20_base` only accepts `Empty`.
+    fn scoped_ref<Q: CustomQuery, T>(&self, deps: Deps<Q>, f: impl FnOnce(Deps) -> T) -> T {
+        let querier = QuerierWrapper::new(&*deps.querier);
+        if self.prefix.is_empty() {
+            return f(Deps {
+                storage: deps.storage,
+                api: deps.api,
+                querier,
+            });
+        }
+        let storage = ReadonlyPrefixedStorage::new(deps.storage, &self.namespace());
+        f(Deps {
+            storage: &storage,
+            api: deps.api,
+            querier,
+        })
+    }
+
+    /// ## Description
+    /// Handles all standard [`Cw20ExecuteMsg`]s for the vault token, making
+    /// any contract using [`Cw4626`] a compliant cw20 token. The messages are
+    /// executed using the `cw20_base` implementations, on the same storage
+    /// that the [`crate::VaultToken`] trait methods use.
+    ///
+    /// `Mint` and `UpdateMinter` are rejected, since only the vault itself can
+    /// mint tokens, through [`Mint::mint`].
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::Unauthorized`] for `Mint` and `UpdateMinter`,
+    /// and may otherwise return any error returned by `cw20_base`.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// pub fn execute(
+    ///     deps: DepsMut,
+    ///     env: Env,
+    ///     info: MessageInfo,
+    ///     msg: ExecuteMsg,
+    /// ) -> Result<Response, ContractError> {
+    ///     match msg {
+    ///         ExecuteMsg::Cw20(msg) => Ok(Cw4626::new(&env).execute(deps, env, info, msg)?),
+    ///         ..
+    ///     }
+    /// }
+    /// ```
+    pub fn execute<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw20ExecuteMsg,
+    ) -> CwTokenResponse<C> {
+        self.scoped(deps, |mut deps| {
+            // accounts whose balances are changed by the message, for the
+            // snapshots. Validated up front so that they are checkpointed under
+            // the same keys `cw20_base` stores their balances under.
+            let accounts = match &msg {
+                Cw20ExecuteMsg::Transfer { recipient, .. } => {
+                    vec![info.sender.to_string(), recipient.clone()]
+                }
+                Cw20ExecuteMsg::Burn { .. } => vec![info.sender.to_string()],
+                Cw20ExecuteMsg::Send { contract, .. } => {
+                    vec![info.sender.to_string(), contract.clone()]
+                }
+                Cw20ExecuteMsg::TransferFrom {
+                    owner, recipient, ..
+                } => vec![owner.clone(), recipient.clone()],
+                Cw20ExecuteMsg::BurnFrom { owner, .. } => vec![owner.clone()],
+                Cw20ExecuteMsg::SendFrom {
+                    owner, contract, ..
+                } => vec![owner.clone(), contract.clone()],
+                _ => vec![],
+            }
+            .iter()
+            .map(|account| deps.api.addr_validate(account))
+            .collect::<StdResult<Vec<_>>>()?;
+            let height = env.block.height;
+
+            let res = match msg {
+                Cw20ExecuteMsg::Transfer { recipient, amount } => {
+                    execute_transfer(deps.branch(), env, info, recipient, amount)
+                }
+                Cw20ExecuteMsg::Burn { amount } => execute_burn(deps.branch(), env, info, amount),
+                Cw20ExecuteMsg::Send {
+                    contract,
+                    amount,
+                    msg,
+                } => execute_send(deps.branch(), env, info, contract, amount, msg),
+                Cw20ExecuteMsg::IncreaseAllowance {
+                    spender,
+                    amount,
+                    expires,
+                } => execute_increase_allowance(deps.branch(), env, info, spender, amount, expires),
+                Cw20ExecuteMsg::DecreaseAllowance {
+                    spender,
+                    amount,
+                    expires,
+                } => execute_decrease_allowance(deps.branch(), env, info, spender, amount, expires),
+                Cw20ExecuteMsg::TransferFrom {
+                    owner,
+                    recipient,
+                    amount,
+                } => execute_transfer_from(deps.branch(), env, info, owner, recipient, amount),
+                Cw20ExecuteMsg::BurnFrom { owner, amount } => {
+                    execute_burn_from(deps.branch(), env, info, owner, amount)
+                }
+                Cw20ExecuteMsg::SendFrom {
+                    owner,
+                    contract,
+                    amount,
+                    msg,
+                } => execute_send_from(deps.branch(), env, info, owner, contract, amount, msg),
+                Cw20ExecuteMsg::UpdateMarketing {
+                    project,
+                    description,
+                    marketing,
+                } => execute_update_marketing(
+                    deps.branch(),
+                    env,
+                    info,
+                    project,
+                    description,
+                    marketing,
+                ),
+                Cw20ExecuteMsg::UploadLogo(logo) => {
+                    execute_upload_logo(deps.branch(), env, info, logo)
+                }
+                // Only the vault itself can mint tokens, through `Mint::mint`
+                Cw20ExecuteMsg::Mint { .. } | Cw20ExecuteMsg::UpdateMinter { .. } => {
+                    return Err(CwTokenError::Unauthorized {})
+                }
+            }?;
+
+            self.checkpoint(deps.storage, height, &accounts.iter().collect::<Vec<_>>())?;
+
+            change_custom(res)
+        })
+    }
+
+    /// ## Description
+    /// Answers all standard cw20 queries for the vault token from the
+    /// `cw20_base` storage that this implementation writes to. Accepts the
+    /// `cw20_base` [`QueryMsg`], which is a superset of
+    /// [`cw20::Cw20QueryMsg`] that also includes `AllSpenderAllowances`.
+    ///
+    /// ## Errors
+    /// May return [`StdError`] if the query fails.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    ///     match msg {
+    ///         QueryMsg::Cw20(msg) => Cw4626::new(&env).query(deps, env, msg),
+    ///         ..
+    ///     }
+    /// }
+    /// ```
+    pub fn query<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        _env: Env,
+        msg: QueryMsg,
+    ) -> StdResult<Binary> {
+        self.scoped_ref(deps, |deps| match msg {
+            QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+            QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+            QueryMsg::Minter {} => to_binary(&query_minter(deps)?),
+            QueryMsg::Allowance { owner, spender } => {
+                to_binary(&query_allowance(deps, owner, spender)?)
+            }
+            QueryMsg::AllAllowances {
+                owner,
+                start_after,
+                limit,
+            } => to_binary(&query_owner_allowances(deps, owner, start_after, limit)?),
+            QueryMsg::AllSpenderAllowances {
+                spender,
+                start_after,
+                limit,
+            } => to_binary(&query_spender_allowances(
+                deps,
+                spender,
+                start_after,
+                limit,
+            )?),
+            QueryMsg::AllAccounts { start_after, limit } => {
+                to_binary(&query_all_accounts(deps, start_after, limit)?)
+            }
+            QueryMsg::MarketingInfo {} => to_binary(&query_marketing_info(deps)?),
+            QueryMsg::DownloadLogo {} => to_binary(&query_download_logo(deps)?),
+        })
+    }
+
+    /// ## Description
+    /// Validates a [`Cw20ReceiveMsg`] hook, sent by this token after a user or
+    /// contract called `Send { contract, amount, msg }` with the vault as
+    /// `contract`. The tokens have already been moved into the contract's
+    /// balance by the `Send`, so the returned `amount` can be treated as
+    /// received, exactly as after a call to [`Cw4626::receive`]. The embedded
+    /// `msg` is decoded into `T` for the vault's handler.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::Unauthorized`] if the hook was not sent by this
+    /// token, [`CwTokenError::ZeroAmount`] if the amount is zero, and
+    /// [`StdError`] if the sender is invalid or `msg` can't be decoded into
+    /// `T`.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// pub fn execute(
+    ///     deps: DepsMut,
+    ///     env: Env,
+    ///     info: MessageInfo,
+    ///     msg: ExecuteMsg,
+    /// ) -> Result<Response, ContractError> {
+    ///     match msg {
+    ///         ExecuteMsg::Receive(msg) => {
+    ///             let hook: Cw20Hook<ReceiveMsg> =
+    ///                 Cw4626::new(&env).receive_cw20(deps.as_ref(), &info, msg)?;
+    ///             match hook.msg {
+    ///                 ReceiveMsg::Redeem { recipient } => {
+    ///                     redeem(deps, env, hook.sender, hook.amount, recipient)
+    ///                 }
+    ///             }
+    ///         }
+    ///         ..
+    ///     }
+    /// }
+    /// ```
+    pub fn receive_cw20<T: DeserializeOwned, Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        info: &MessageInfo,
+        msg: Cw20ReceiveMsg,
+    ) -> CwTokenResult<Cw20Hook<T>> {
+        if info.sender != self.address {
+            return Err(CwTokenError::Unauthorized {});
+        }
+        if msg.amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
         }
+
+        Ok(Cw20Hook {
+            sender: deps.api.addr_validate(&msg.sender)?,
+            amount: msg.amount,
+            msg: from_binary(&msg.msg)?,
+        })
+    }
+
+    /// ## Description
+    /// Returns the balance of `address` at the start of block `height`, i.e.
+    /// before any of the transactions in that block were executed.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::SnapshotsDisabled`] if the token was not
+    /// instantiated with [`Cw4626InstantiateMsg::snapshots`], or [`StdError`]
+    /// if `address` is invalid.
+    pub fn query_balance_at_height<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        address: impl Into<String>,
+        height: u64,
+    ) -> CwTokenResult<Uint128> {
+        self.scoped_ref(deps, |deps| {
+            assert_snapshots_enabled(deps.storage)?;
+            let address = deps.api.addr_validate(&address.into())?;
+            Ok(BALANCE_SNAPSHOTS
+                .may_load_at_height(deps.storage, &address, height)?
+                .unwrap_or_default())
+        })
+    }
+
+    /// ## Description
+    /// Returns the total supply at the start of block `height`, i.e. before
+    /// any of the transactions in that block were executed.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::SnapshotsDisabled`] if the token was not
+    /// instantiated with [`Cw4626InstantiateMsg::snapshots`].
+    pub fn query_total_supply_at_height<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        height: u64,
+    ) -> CwTokenResult<Uint128> {
+        self.scoped_ref(deps, |deps| {
+            assert_snapshots_enabled(deps.storage)?;
+            Ok(SUPPLY_SNAPSHOTS
+                .may_load_at_height(deps.storage, height)?
+                .unwrap_or_default())
+        })
+    }
+
+    /// Records the current balances of `accounts` and the total supply as of
+    /// `height`, if snapshots are enabled. Must be called after every change
+    /// to `BALANCES` or `TOKEN_INFO`.
+    fn checkpoint(
+        &self,
+        storage: &mut dyn Storage,
+        height: u64,
+        accounts: &[&Addr],
+    ) -> StdResult<()> {
+        if !SNAPSHOTS_ENABLED.may_load(storage)?.unwrap_or(false) {
+            return Ok(());
+        }
+        for account in accounts {
+            let balance = BALANCES.may_load(storage, account)?.unwrap_or_default();
+            BALANCE_SNAPSHOTS.save(storage, account, &balance, height)?;
+        }
+        let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+        SUPPLY_SNAPSHOTS.save(storage, &total_supply, height)
+    }
+}
+
+/// Returns [`CwTokenError::SnapshotsDisabled`] unless snapshots are enabled.
+fn assert_snapshots_enabled(storage: &dyn Storage) -> CwTokenResult<()> {
+    match SNAPSHOTS_ENABLED.may_load(storage)?.unwrap_or(false) {
+        true => Ok(()),
+        false => Err(CwTokenError::SnapshotsDisabled {}),
+    }
+}
+
+/// Converts a `cw20_base` response into one with custom messages of type `C`.
+/// Wasm and bank messages are converted, any other message is an error.
+fn change_custom<C: CustomMsg>(res: Response) -> CwTokenResponse<C> {
+    let messages = res
+        .messages
+        .into_iter()
+        .map(|sub_msg| {
+            let msg = match sub_msg.msg {
+                CosmosMsg::Wasm(msg) => CosmosMsg::Wasm(msg),
+                CosmosMsg::Bank(msg) => CosmosMsg::Bank(msg),
+                msg => {
+                    return Err(StdError::generic_err(format!(
+                        "unsupported cw20_base message: {msg:?}"
+                    )))
+                }
+            };
+            Ok(SubMsg {
+                id: sub_msg.id,
+                msg,
+                gas_limit: sub_msg.gas_limit,
+                reply_on: sub_msg.reply_on,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut custom_res = Response::new()
+        .add_submessages(messages)
+        .add_attributes(res.attributes)
+        .add_events(res.events);
+    if let Some(data) = res.data {
+        custom_res = custom_res.set_data(data);
     }
+    Ok(custom_res)
+}
+
+/// A validated [`Cw20ReceiveMsg`] hook, returned by [`Cw4626::receive_cw20`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cw20Hook<T> {
+    /// The original sender of the tokens
+    pub sender: Addr,
+    /// The amount of tokens received into the contract's balance
+    pub amount: Uint128,
+    /// The decoded hook message
+    pub msg: T,
 }
 
 impl Display for Cw4626 {
@@ -63,86 +538,227 @@ impl Display for Cw4626 {
     }
 }
 
-impl VaultToken for Cw4626 {
-    fn query_balance<A: Into<String>>(&self, deps: Deps, address: A) -> CwTokenResult<Uint128> {
-        Ok(query_balance(deps, address.into())?.balance)
+impl<Q: CustomQuery> Query<Q> for Cw4626 {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
+        self.scoped_ref(
+            deps,
+            |deps| Ok(query_balance(deps, address.into())?.balance),
+        )
+    }
+
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        self.scoped_ref(deps, |deps| Ok(TOKEN_INFO.load(deps.storage)?.total_supply))
     }
 
-    fn query_total_supply(&self, deps: Deps) -> CwTokenResult<Uint128> {
-        Ok(TOKEN_INFO.load(deps.storage)?.total_supply)
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        self.scoped_ref(deps, |deps| {
+            Ok(InflationProtection::load(deps.storage, &self.to_string())?)
+        })
     }
 }
 
-impl Mint for Cw4626 {
+impl<C: CustomMsg, Q: CustomQuery> Mint<C, Q> for Cw4626 {
+    /// Mint vault tokens to the `recipient`. If
+    /// [`InflationProtection::DeadShares`] is configured, the dead shares are
+    /// minted to the contract out of `amount` on the first mint.
     fn mint(
         &self,
-        deps: DepsMut,
-        _env: &Env,
+        deps: DepsMut<Q>,
+        env: &Env,
         recipient: &Addr,
         amount: Uint128,
-    ) -> CwTokenResponse {
-        // Here we must copy-paste the code from cw20_base, because cw20 base does not
-        // allow anyone to mint, and here we want anyone to be able to mint as long as
-        // they deposit the correct depositable assets
-        let recipient: String = recipient.to_string();
-
-        if amount == Uint128::zero() {
-            return Err(ContractError::InvalidZeroAmount {}.into());
-        }
+    ) -> CwTokenResponse<C> {
+        self.scoped(deps, |deps| {
+            // Here we must copy-paste the code from cw20_base, because cw20 base does not
+            // allow anyone to mint, and here we want anyone to be able to mint as long as
+            // they deposit the correct depositable assets
+            let recipient: String = recipient.to_string();
+
+            if amount.is_zero() {
+                return Err(CwTokenError::ZeroAmount {});
+            }
 
-        let mut config = TOKEN_INFO
-            .may_load(deps.storage)?
-            .ok_or(ContractError::Unauthorized {})?;
+            // lock dead shares in the contract on the first mint, if configured
+            let dead_shares = take_dead_shares(deps.storage, &self.to_string(), amount)?;
 
-        // update supply
-        config.total_supply += amount;
+            let mut config = TOKEN_INFO
+                .may_load(deps.storage)?
+                .ok_or(CwTokenError::Unauthorized {})?;
 
-        TOKEN_INFO.save(deps.storage, &config)?;
+            // update supply
+            config.total_supply += amount;
 
-        // add amount to recipient balance
-        let rcpt_addr = deps.api.addr_validate(&recipient)?;
-        BALANCES.update(
-            deps.storage,
-            &rcpt_addr,
-            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-        )?;
+            TOKEN_INFO.save(deps.storage, &config)?;
 
-        let event = Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
-            attr("action", "mint"),
-            attr("vault_token_address", self.to_string()),
-            attr("amount", amount.to_string()),
-            attr("recipient", recipient),
-        ]);
+            if !dead_shares.is_zero() {
+                BALANCES.update(
+                    deps.storage,
+                    &env.contract.address,
+                    |balance: Option<Uint128>| -> StdResult<_> {
+                        Ok(balance.unwrap_or_default() + dead_shares)
+                    },
+                )?;
+            }
+
+            // add amount to recipient balance
+            let rcpt_addr = deps.api.addr_validate(&recipient)?;
+            BALANCES.update(
+                deps.storage,
+                &rcpt_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + amount - dead_shares)
+                },
+            )?;
+
+            let mut accounts = vec![&rcpt_addr];
+            if !dead_shares.is_zero() {
+                accounts.push(&env.contract.address);
+            }
+            self.checkpoint(deps.storage, env.block.height, &accounts)?;
 
-        let res = Response::new().add_event(event);
-        Ok(res)
+            let mut event = self.event("mint").add_attributes(vec![
+                attr("amount", amount.to_string()),
+                attr("recipient", recipient),
+            ]);
+            if !dead_shares.is_zero() {
+                event = event.add_attribute("dead_shares", dead_shares.to_string());
+            }
+
+            let res = Response::new().add_event(event);
+            Ok(res)
+        })
     }
 }
 
-impl Burn for Cw4626 {
-    fn burn(&self, deps: DepsMut, env: &Env, amount: Uint128) -> CwTokenResponse {
-        // lower balance
-        BALANCES.update(
-            deps.storage,
-            &env.contract.address,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default().checked_sub(amount)?)
-            },
-        )?;
-        // reduce total_supply
-        TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
-            meta.total_supply = meta.total_supply.checked_sub(amount)?;
-            Ok(meta)
-        })?;
+impl<C: CustomMsg, Q: CustomQuery> Burn<C, Q> for Cw4626 {
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        self.scoped(deps, |deps| {
+            if amount.is_zero() {
+                return Err(CwTokenError::ZeroAmount {});
+            }
 
-        let event = Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
-            attr("action", "burn"),
-            attr("vault_token_address", self.to_string()),
-            attr("amount", amount.to_string()),
-        ]);
+            // lower balance
+            BALANCES.update(
+                deps.storage,
+                &env.contract.address,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_sub(amount)?)
+                },
+            )?;
+            // reduce total_supply
+            TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
+                meta.total_supply = meta.total_supply.checked_sub(amount)?;
+                Ok(meta)
+            })?;
+
+            self.checkpoint(deps.storage, env.block.height, &[&env.contract.address])?;
+
+            let event = self
+                .event("burn")
+                .add_attributes(vec![attr("amount", amount.to_string())]);
 
-        let res = Response::new().add_event(event);
-        Ok(res)
+            let res = Response::new().add_event(event);
+            Ok(res)
+        })
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> BurnFrom<C, Q> for Cw4626 {
+    /// Burn vault tokens from the `owner`'s balance, spending the allowance
+    /// that the owner has given the contract (`env.contract.address`).
+    fn burn_from(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        owner: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        self.scoped(deps, |deps| {
+            if amount.is_zero() {
+                return Err(CwTokenError::ZeroAmount {});
+            }
+
+            let owner_addr = deps.api.addr_validate(owner.as_str())?;
+
+            // deduct allowance before doing anything else, this also checks expiry
+            deduct_allowance(
+                deps.storage,
+                &owner_addr,
+                &env.contract.address,
+                &env.block,
+                amount,
+            )?;
+
+            // lower balance
+            BALANCES.update(
+                deps.storage,
+                &owner_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_sub(amount)?)
+                },
+            )?;
+            // reduce total_supply
+            TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
+                meta.total_supply = meta.total_supply.checked_sub(amount)?;
+                Ok(meta)
+            })?;
+
+            self.checkpoint(deps.storage, env.block.height, &[&owner_addr])?;
+
+            let event = self.event("burn_from").add_attributes(vec![
+                attr("amount", amount.to_string()),
+                attr("owner", owner_addr.to_string()),
+            ]);
+
+            Ok(Response::new().add_event(event))
+        })
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for Cw4626 {
+    /// Transfer vault tokens from the contract's balance to the `recipient`.
+    fn transfer(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        self.scoped(deps, |deps| {
+            if amount.is_zero() {
+                return Err(CwTokenError::ZeroAmount {});
+            }
+
+            let rcpt_addr = deps.api.addr_validate(recipient.as_str())?;
+
+            BALANCES.update(
+                deps.storage,
+                &env.contract.address,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_sub(amount)?)
+                },
+            )?;
+            BALANCES.update(
+                deps.storage,
+                &rcpt_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + amount)
+                },
+            )?;
+
+            self.checkpoint(
+                deps.storage,
+                env.block.height,
+                &[&env.contract.address, &rcpt_addr],
+            )?;
+
+            let event = self.event("transfer").add_attributes(vec![
+                attr("amount", amount.to_string()),
+                attr("recipient", rcpt_addr.to_string()),
+            ]);
+
+            Ok(Response::new().add_event(event))
+        })
     }
 }
 
@@ -159,6 +775,17 @@ pub struct Cw4626InstantiateMsg {
     pub decimals: u8,
     /// Optional marketing info
     pub marketing: Option<InstantiateMarketingInfo>,
+    /// Optional protection against the inflation attack. Defaults to
+    /// [`InflationProtection::None`].
+    pub inflation_protection: Option<InflationProtection>,
+    /// Whether to record height-indexed snapshots of all balances and the
+    /// total supply, which can be queried with
+    /// [`Cw4626::query_balance_at_height`] and
+    /// [`Cw4626::query_total_supply_at_height`], e.g. for governance voting
+    /// power. Snapshots cost extra gas on every balance change. Defaults to
+    /// `false`.
+    #[serde(default)]
+    pub snapshots: bool,
 }
 
 impl From<Cw4626InstantiateMsg> for InstantiateMsg {
@@ -174,85 +801,111 @@ impl From<Cw4626InstantiateMsg> for InstantiateMsg {
     }
 }
 
-impl Instantiate for Cw4626 {
-    fn instantiate(&self, deps: DepsMut, init_info: Option<Binary>) -> CwTokenResponse {
-        let msg: InstantiateMsg = from_binary::<Cw4626InstantiateMsg>(
-            &init_info.ok_or_else(|| StdError::generic_err("init_info requried for Cw4626"))?,
-        )?
-        .into();
+impl<C: CustomMsg, Q: CustomQuery> Instantiate<C, Q> for Cw4626 {
+    type InitInfo = Cw4626InstantiateMsg;
 
-        // check valid token info
-        msg.validate()?;
+    fn instantiate(&self, deps: DepsMut<Q>, msg: Cw4626InstantiateMsg) -> CwTokenResponse<C> {
+        self.scoped(deps, |deps| {
+            if let Some(inflation_protection) = &msg.inflation_protection {
+                inflation_protection.save(deps.storage, &self.to_string())?;
+            }
+            SNAPSHOTS_ENABLED.save(deps.storage, &msg.snapshots)?;
 
-        // store token info
-        let data = TokenInfo {
-            name: msg.name,
-            symbol: msg.symbol,
-            decimals: msg.decimals,
-            total_supply: Uint128::zero(),
-            mint: None,
-        };
-        TOKEN_INFO.save(deps.storage, &data)?;
-
-        if let Some(marketing) = msg.marketing {
-            let data = MarketingInfoResponse {
-                project: marketing.project,
-                description: marketing.description,
-                marketing: marketing
-                    .marketing
-                    .map(|addr| deps.api.addr_validate(&addr))
-                    .transpose()?,
-                logo: None,
+            let msg: InstantiateMsg = msg.into();
+
+            // check valid token info
+            msg.validate()?;
+
+            // store token info
+            let data = TokenInfo {
+                name: msg.name,
+                symbol: msg.symbol,
+                decimals: msg.decimals,
+                total_supply: Uint128::zero(),
+                mint: None,
             };
-            MARKETING_INFO.save(deps.storage, &data)?;
-        }
+            TOKEN_INFO.save(deps.storage, &data)?;
 
-        let event = Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
-            attr("action", "instantiate"),
-            attr("name", data.name),
-            attr("symbol", data.symbol),
-            attr("decimals", data.decimals.to_string()),
-        ]);
+            if let Some(marketing) = msg.marketing {
+                let data = MarketingInfoResponse {
+                    project: marketing.project,
+                    description: marketing.description,
+                    marketing: marketing
+                        .marketing
+                        .map(|addr| deps.api.addr_validate(&addr))
+                        .transpose()?,
+                    logo: None,
+                };
+                MARKETING_INFO.save(deps.storage, &data)?;
+            }
 
-        Ok(Response::default().add_event(event))
+            let event = Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
+                attr("action", "instantiate"),
+                attr("name", data.name),
+                attr("symbol", data.symbol),
+                attr("decimals", data.decimals.to_string()),
+            ]);
+
+            Ok(Response::default().add_event(event))
+        })
     }
 }
 
-impl Receive for Cw4626 {
+impl<Q: CustomQuery> Receive<Q> for Cw4626 {
     /// Recieve the vault token from the caller's (info.sender) balance into the
-    /// contract's balance.
+    /// contract's balance. Since no native funds are consumed, all of
+    /// `info.funds` is returned.
     fn receive(
         &self,
-        deps: DepsMut,
+        deps: DepsMut<Q>,
         env: &Env,
         info: &MessageInfo,
         amount: Uint128,
-    ) -> StdResult<()> {
-        let rcpt_addr = &env.contract.address;
-        let owner_addr = &info.sender;
-
-        BALANCES.update(
-            deps.storage,
-            owner_addr,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default().checked_sub(amount)?)
-            },
-        )?;
-        BALANCES.update(
-            deps.storage,
-            rcpt_addr,
-            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-        )?;
-        Ok(())
+    ) -> CwTokenResult<Vec<Coin>> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        self.scoped(deps, |deps| {
+            let rcpt_addr = &env.contract.address;
+            let owner_addr = &info.sender;
+
+            BALANCES.update(
+                deps.storage,
+                owner_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_sub(amount)?)
+                },
+            )?;
+            BALANCES.update(
+                deps.storage,
+                rcpt_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + amount)
+                },
+            )?;
+            self.checkpoint(deps.storage, env.block.height, &[owner_addr, rcpt_addr])?;
+
+            Ok(info.funds.clone())
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
-    use cosmwasm_std::{to_binary, MemoryStorage, OverflowError, OverflowOperation, OwnedDeps};
+    use std::marker::PhantomData;
 
-    use crate::CwTokenError;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{
+        Empty, MemoryStorage, Order, OverflowError, OverflowOperation, OwnedDeps, StdError, WasmMsg,
+    };
+    use cw20::{
+        AllAccountsResponse, AllSpenderAllowancesResponse, AllowanceResponse, BalanceResponse,
+        MinterResponse, TokenInfoResponse,
+    };
+    use cw20_base::ContractError;
+    use cw_utils::Expiration;
+    use test_case::test_case;
 
     use super::*;
 
@@ -264,9 +917,11 @@ mod test {
             symbol: "vaultToken".to_string(),
             decimals: 6,
             marketing: None,
+            inflation_protection: None,
+            snapshots: false,
         };
 
-        cw4626.instantiate(deps, Some(to_binary(&msg)?))
+        cw4626.instantiate(deps, msg)
     }
 
     fn setup_and_mint(
@@ -278,11 +933,12 @@ mod test {
 
         let cw4626 = Cw4626 {
             address: Addr::unchecked("cw4626"),
+            prefix: String::new(),
         };
 
         instantiate_cw4626(cw4626.clone(), deps.as_mut()).unwrap();
 
-        cw4626
+        let _: Response = cw4626
             .mint(
                 deps.as_mut(),
                 &env,
@@ -299,6 +955,7 @@ mod test {
         let mut deps = mock_dependencies();
         let cw4626 = Cw4626 {
             address: Addr::unchecked("cw4626"),
+            prefix: String::new(),
         };
 
         instantiate_cw4626(cw4626, deps.as_mut()).unwrap();
@@ -312,6 +969,35 @@ mod test {
         assert_eq!(token_info.mint, None);
     }
 
+    #[test]
+    fn test_instantiate_binary() {
+        let mut deps = mock_dependencies();
+        let cw4626 = Cw4626::new(&mock_env());
+
+        // Init info is required
+        let res =
+            Instantiate::<Empty>::instantiate_binary(&cw4626, deps.as_mut(), None).unwrap_err();
+        assert_eq!(
+            res,
+            CwTokenError::Std(StdError::generic_err("init_info required"))
+        );
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        };
+        let _: Response = cw4626
+            .instantiate_binary(deps.as_mut(), Some(to_binary(&msg).unwrap()))
+            .unwrap();
+
+        let token_info = TOKEN_INFO.load(deps.as_ref().storage).unwrap();
+        assert_eq!(token_info.name, "Cw4626 tokenized vault");
+    }
+
     #[test]
     fn test_mint_and_burn() {
         // Setup and mint 1000 tokens
@@ -328,7 +1014,7 @@ mod test {
 
         // Try burning more than was minted
         let burn_amount = Uint128::from(5000u128);
-        let res = cw4626.burn(deps.as_mut(), &env, burn_amount).unwrap_err();
+        let res = Burn::<Empty>::burn(&cw4626, deps.as_mut(), &env, burn_amount).unwrap_err();
 
         // Assert error message
         assert_eq!(
@@ -344,7 +1030,7 @@ mod test {
 
         // Burn correct amount
         let burn_amount = Uint128::from(500u128);
-        cw4626.burn(deps.as_mut(), &env, burn_amount).unwrap();
+        let _: Response = cw4626.burn(deps.as_mut(), &env, burn_amount).unwrap();
 
         // Assert correct balance was burned
         let balance = BALANCES.load(&deps.storage, &env.contract.address).unwrap();
@@ -355,6 +1041,22 @@ mod test {
         assert_eq!(token_info.total_supply, mint_amount - burn_amount);
     }
 
+    #[test]
+    fn test_zero_amount() {
+        let (mut deps, env, cw4626) = setup_and_mint(Uint128::from(1000u128), None);
+        let recipient = Addr::unchecked("recipient");
+
+        let res = Mint::<Empty>::mint(&cw4626, deps.as_mut(), &env, &recipient, Uint128::zero())
+            .unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+        let res = Burn::<Empty>::burn(&cw4626, deps.as_mut(), &env, Uint128::zero()).unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+        let res =
+            Transfer::<Empty>::transfer(&cw4626, deps.as_mut(), &env, &recipient, Uint128::zero())
+                .unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+    }
+
     #[test]
     fn test_vault_token_queries() {
         // Setup and mint 1000 tokens
@@ -390,13 +1092,13 @@ mod test {
         // Assert overflow error message
         assert_eq!(
             res,
-            StdError::Overflow {
+            CwTokenError::Std(StdError::Overflow {
                 source: OverflowError {
                     operation: OverflowOperation::Sub,
                     operand1: mint_amount.to_string(),
                     operand2: receive_amount.to_string(),
                 }
-            }
+            })
         );
 
         // Receive 500 tokens
@@ -418,10 +1120,894 @@ mod test {
         assert_eq!(token_info.total_supply, mint_amount);
     }
 
+    #[test]
+    fn test_transfer() {
+        let recipient = Addr::unchecked("recipient");
+
+        // Setup and mint 1000 tokens to the contract
+        let mint_amount = Uint128::from(1000u128);
+        let (mut deps, env, cw4626) = setup_and_mint(mint_amount, None);
+
+        // Try transferring more than the contract holds
+        let transfer_amount = Uint128::from(5000u128);
+        let res =
+            Transfer::<Empty>::transfer(&cw4626, deps.as_mut(), &env, &recipient, transfer_amount)
+                .unwrap_err();
+
+        // Assert overflow error message
+        assert_eq!(
+            res,
+            CwTokenError::Std(StdError::Overflow {
+                source: OverflowError {
+                    operation: OverflowOperation::Sub,
+                    operand1: mint_amount.to_string(),
+                    operand2: transfer_amount.to_string(),
+                }
+            })
+        );
+
+        // Transfer 400 tokens
+        let transfer_amount = Uint128::from(400u128);
+        let res: Response = cw4626
+            .transfer(deps.as_mut(), &env, &recipient, transfer_amount)
+            .unwrap();
+
+        // Assert correct event
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
+                    attr("action", "transfer"),
+                    attr("vault_token_address", cw4626.to_string()),
+                    attr("amount", transfer_amount.to_string()),
+                    attr("recipient", recipient.to_string()),
+                ])
+            ]
+        );
+
+        // Assert correct balances
+        let balance = BALANCES.load(&deps.storage, &env.contract.address).unwrap();
+        assert_eq!(balance, mint_amount - transfer_amount);
+        let balance = BALANCES.load(&deps.storage, &recipient).unwrap();
+        assert_eq!(balance, transfer_amount);
+
+        // Assert total supply is unchanged
+        let token_info = TOKEN_INFO.load(&deps.storage).unwrap();
+        assert_eq!(token_info.total_supply, mint_amount);
+    }
+
+    #[test]
+    fn test_execute_transfer_and_send() {
+        let sender = Addr::unchecked(SENDER);
+        let info = mock_info(SENDER, &[]);
+
+        // Setup and mint 1000 tokens to sender
+        let mint_amount = Uint128::from(1000u128);
+        let (mut deps, env, cw4626) = setup_and_mint(mint_amount, Some(&sender));
+
+        // Transfer 300 tokens to recipient
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                Cw20ExecuteMsg::Transfer {
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::from(300u128),
+                },
+            )
+            .unwrap();
+
+        // Send 200 tokens to a contract
+        let res: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env,
+                info,
+                Cw20ExecuteMsg::Send {
+                    contract: "contract".to_string(),
+                    amount: Uint128::from(200u128),
+                    msg: Binary::default(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // Assert correct balances
+        let balance = cw4626.query_balance(deps.as_ref(), SENDER).unwrap();
+        assert_eq!(balance, Uint128::from(500u128));
+        let balance = cw4626.query_balance(deps.as_ref(), "recipient").unwrap();
+        assert_eq!(balance, Uint128::from(300u128));
+        let balance = cw4626.query_balance(deps.as_ref(), "contract").unwrap();
+        assert_eq!(balance, Uint128::from(200u128));
+    }
+
+    #[cw_serde]
+    enum TestMsg {
+        Noop {},
+    }
+
+    impl CustomMsg for TestMsg {}
+
+    #[cw_serde]
+    enum TestQuery {
+        Noop {},
+    }
+
+    impl CustomQuery for TestQuery {}
+
+    #[test]
+    fn test_custom_msg_and_query() {
+        let env = mock_env();
+        let sender = Addr::unchecked(SENDER);
+        let cw4626 = Cw4626::new(&env);
+        let mut deps = OwnedDeps {
+            storage: MemoryStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::<TestQuery>::new(&[]),
+            custom_query_type: PhantomData::<TestQuery>,
+        };
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        };
+        let _: Response<TestMsg> = cw4626.instantiate(deps.as_mut(), msg).unwrap();
+        let _: Response<TestMsg> = cw4626
+            .mint(deps.as_mut(), &env, &sender, Uint128::from(1000u128))
+            .unwrap();
+
+        // The wasm message of a cw20 Send is converted to the custom message type
+        let res: Response<TestMsg> = cw4626
+            .execute(
+                deps.as_mut(),
+                env,
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Send {
+                    contract: "contract".to_string(),
+                    amount: Uint128::from(400u128),
+                    msg: Binary::default(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "contract".to_string(),
+                msg: Cw20ReceiveMsg {
+                    sender: SENDER.to_string(),
+                    amount: Uint128::from(400u128),
+                    msg: Binary::default(),
+                }
+                .into_binary()
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        assert_eq!(
+            cw4626.query_balance(deps.as_ref(), SENDER).unwrap(),
+            Uint128::from(600u128)
+        );
+        assert_eq!(
+            cw4626.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::from(1000u128)
+        );
+    }
+
+    #[cw_serde]
+    enum HookMsg {
+        Redeem { recipient: String },
+    }
+
+    #[test]
+    fn test_receive_cw20() {
+        let sender = Addr::unchecked(SENDER);
+        let (mut deps, env, cw4626) = setup_and_mint(Uint128::from(1000u128), Some(&sender));
+        let hook = to_binary(&HookMsg::Redeem {
+            recipient: "recipient".to_string(),
+        })
+        .unwrap();
+
+        // Send 400 tokens to the vault contract itself
+        let res: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env,
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Send {
+                    contract: cw4626.to_string(),
+                    amount: Uint128::from(400u128),
+                    msg: hook.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let balance = cw4626
+            .query_balance(deps.as_ref(), &cw4626.address)
+            .unwrap();
+        assert_eq!(balance, Uint128::from(400u128));
+
+        // The hook is then handled by the vault
+        let msg = Cw20ReceiveMsg {
+            sender: SENDER.to_string(),
+            amount: Uint128::from(400u128),
+            msg: hook,
+        };
+        let info = mock_info(cw4626.address.as_str(), &[]);
+        let received: Cw20Hook<HookMsg> = cw4626
+            .receive_cw20(deps.as_ref(), &info, msg.clone())
+            .unwrap();
+        assert_eq!(
+            received,
+            Cw20Hook {
+                sender,
+                amount: Uint128::from(400u128),
+                msg: HookMsg::Redeem {
+                    recipient: "recipient".to_string()
+                },
+            }
+        );
+
+        // Hooks from other cw20 tokens are rejected
+        let err = cw4626
+            .receive_cw20::<HookMsg, _>(deps.as_ref(), &mock_info("other_token", &[]), msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, CwTokenError::Unauthorized {}));
+
+        // Zero amounts and undecodable messages are rejected
+        let err = cw4626
+            .receive_cw20::<HookMsg, _>(
+                deps.as_ref(),
+                &info,
+                Cw20ReceiveMsg {
+                    amount: Uint128::zero(),
+                    ..msg.clone()
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, CwTokenError::ZeroAmount {}));
+        let err = cw4626
+            .receive_cw20::<HookMsg, _>(
+                deps.as_ref(),
+                &info,
+                Cw20ReceiveMsg {
+                    msg: Binary::default(),
+                    ..msg
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, CwTokenError::Std(_)));
+    }
+
+    #[test]
+    fn test_execute_allowances() {
+        let owner = Addr::unchecked(SENDER);
+        let spender_info = mock_info("spender", &[]);
+
+        // Setup and mint 1000 tokens to owner
+        let mint_amount = Uint128::from(1000u128);
+        let (mut deps, env, cw4626) = setup_and_mint(mint_amount, Some(&owner));
+
+        // Increase allowance of spender
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "spender".to_string(),
+                    amount: Uint128::from(400u128),
+                    expires: None,
+                },
+            )
+            .unwrap();
+
+        // Try to transfer more than the allowance
+        let res = cw4626
+            .execute::<Empty, _>(
+                deps.as_mut(),
+                env.clone(),
+                spender_info.clone(),
+                Cw20ExecuteMsg::TransferFrom {
+                    owner: SENDER.to_string(),
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::from(500u128),
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(
+            res,
+            CwTokenError::Cw20ContractError(ContractError::Std(StdError::Overflow { .. }))
+        ));
+
+        // Transfer within the allowance
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env,
+                spender_info,
+                Cw20ExecuteMsg::TransferFrom {
+                    owner: SENDER.to_string(),
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::from(400u128),
+                },
+            )
+            .unwrap();
+
+        // Assert correct balances
+        let balance = cw4626.query_balance(deps.as_ref(), SENDER).unwrap();
+        assert_eq!(balance, Uint128::from(600u128));
+        let balance = cw4626.query_balance(deps.as_ref(), "recipient").unwrap();
+        assert_eq!(balance, Uint128::from(400u128));
+    }
+
+    #[test]
+    fn test_execute_mint_is_rejected() {
+        let (mut deps, env, cw4626) = setup_and_mint(Uint128::from(1000u128), None);
+
+        let res = cw4626
+            .execute::<Empty, _>(
+                deps.as_mut(),
+                env,
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Mint {
+                    recipient: SENDER.to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+            )
+            .unwrap_err();
+
+        assert_eq!(res, CwTokenError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_burn_from() {
+        let owner = Addr::unchecked(SENDER);
+        let burn_amount = Uint128::from(300u128);
+
+        // Setup and mint 1000 tokens to owner
+        let mint_amount = Uint128::from(1000u128);
+        let (mut deps, mut env, cw4626) = setup_and_mint(mint_amount, Some(&owner));
+
+        // Try burning without allowance
+        let res = BurnFrom::<Empty>::burn_from(&cw4626, deps.as_mut(), &env, &owner, burn_amount)
+            .unwrap_err();
+        assert_eq!(
+            res,
+            CwTokenError::Cw20ContractError(ContractError::NoAllowance {})
+        );
+
+        // Give the contract an allowance that expires at the next block
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: env.contract.address.to_string(),
+                    amount: burn_amount,
+                    expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                },
+            )
+            .unwrap();
+
+        // Try burning more than the allowance
+        let res = BurnFrom::<Empty>::burn_from(
+            &cw4626,
+            deps.as_mut(),
+            &env,
+            &owner,
+            burn_amount + Uint128::one(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            res,
+            CwTokenError::Cw20ContractError(ContractError::Std(StdError::Overflow { .. }))
+        ));
+
+        // Burn within the allowance
+        let res: Response = cw4626
+            .burn_from(deps.as_mut(), &env, &owner, Uint128::from(100u128))
+            .unwrap();
+
+        // Assert correct event
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("apollo/cw-vault-token/cw4626").add_attributes(vec![
+                    attr("action", "burn_from"),
+                    attr("vault_token_address", cw4626.to_string()),
+                    attr("amount", "100"),
+                    attr("owner", SENDER),
+                ])
+            ]
+        );
+
+        // Assert correct balance and total supply
+        let balance = cw4626.query_balance(deps.as_ref(), SENDER).unwrap();
+        assert_eq!(balance, Uint128::from(900u128));
+        let total_supply = cw4626.query_total_supply(deps.as_ref()).unwrap();
+        assert_eq!(total_supply, Uint128::from(900u128));
+
+        // Try burning after the allowance has expired
+        env.block.height += 1;
+        let res = BurnFrom::<Empty>::burn_from(
+            &cw4626,
+            deps.as_mut(),
+            &env,
+            &owner,
+            Uint128::from(100u128),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            CwTokenError::Cw20ContractError(ContractError::Expired {})
+        );
+    }
+
+    #[test]
+    fn test_prefixed_tokens_are_independent() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let user = Addr::unchecked(SENDER);
+        let first = Cw4626::new(&env).with_prefix("first");
+        let second = Cw4626::new(&env).with_prefix("second");
+
+        let mut msg = Cw4626InstantiateMsg {
+            name: "First vault".to_string(),
+            symbol: "vaultOne".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(100u128),
+            }),
+            snapshots: false,
+        };
+        let _: Response = first.instantiate(deps.as_mut(), msg.clone()).unwrap();
+        msg.name = "Second vault".to_string();
+        msg.inflation_protection = None;
+        let _: Response = second.instantiate(deps.as_mut(), msg).unwrap();
+
+        // Dead shares are only taken by the first token
+        let _: Response = first
+            .mint(deps.as_mut(), &env, &user, Uint128::from(1000u128))
+            .unwrap();
+        let _: Response = second
+            .mint(deps.as_mut(), &env, &user, Uint128::from(500u128))
+            .unwrap();
+        assert_eq!(
+            first.query_balance(deps.as_ref(), SENDER).unwrap(),
+            Uint128::from(900u128)
+        );
+        assert_eq!(
+            second.query_balance(deps.as_ref(), SENDER).unwrap(),
+            Uint128::from(500u128)
+        );
+        assert_eq!(
+            first.query_inflation_protection(deps.as_ref()).unwrap(),
+            InflationProtection::DeadShares {
+                amount: Uint128::from(100u128)
+            }
+        );
+        assert_eq!(
+            second.query_inflation_protection(deps.as_ref()).unwrap(),
+            InflationProtection::None
+        );
+
+        // cw20 messages and queries are scoped too
+        let _: Response = second
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(200u128),
+                },
+            )
+            .unwrap();
+        let res: TokenInfoResponse = from_binary(
+            &second
+                .query(deps.as_ref(), env.clone(), QueryMsg::TokenInfo {})
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.name, "Second vault");
+        assert_eq!(res.total_supply, Uint128::from(300u128));
+        assert_eq!(
+            first.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::from(1000u128)
+        );
+
+        // Nothing is written to the unprefixed cw20_base layout
+        assert_eq!(TOKEN_INFO.may_load(&deps.storage).unwrap(), None);
+        assert_eq!(BALANCES.may_load(&deps.storage, &user).unwrap(), None);
+    }
+
+    #[test_case("balance" ; "cw20 balances namespace")]
+    #[test_case("token_info" ; "cw20 token info key")]
+    #[test_case("allowance" ; "cw20 allowances namespace")]
+    fn test_prefix_does_not_collide_with_cw20_base(prefix: &str) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let user = Addr::unchecked(SENDER);
+        let unprefixed = Cw4626::new(&env);
+        let prefixed = Cw4626::new(&env).with_prefix(prefix);
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        };
+        let _: Response = unprefixed.instantiate(deps.as_mut(), msg.clone()).unwrap();
+        let _: Response = prefixed.instantiate(deps.as_mut(), msg).unwrap();
+
+        let unprefixed_res: Response = unprefixed
+            .mint(deps.as_mut(), &env, &user, Uint128::from(1000u128))
+            .unwrap();
+        let prefixed_res: Response = prefixed
+            .mint(deps.as_mut(), &env, &user, Uint128::from(10u128))
+            .unwrap();
+
+        // Only the events of the prefixed token carry its prefix
+        assert_eq!(
+            unprefixed_res.events[0]
+                .attributes
+                .iter()
+                .find(|attr| attr.key == "prefix"),
+            None
+        );
+        assert_eq!(
+            prefixed_res.events[0].attributes[..3],
+            [
+                attr("action", "mint"),
+                attr("vault_token_address", env.contract.address.to_string()),
+                attr("prefix", prefix),
+            ]
+        );
+
+        assert_eq!(
+            unprefixed.query_balance(deps.as_ref(), SENDER).unwrap(),
+            Uint128::from(1000u128)
+        );
+        assert_eq!(
+            prefixed.query_balance(deps.as_ref(), SENDER).unwrap(),
+            Uint128::from(10u128)
+        );
+        assert_eq!(
+            unprefixed.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::from(1000u128)
+        );
+        assert_eq!(
+            BALANCES
+                .keys(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let user = Addr::unchecked(SENDER);
+        let cw4626 = Cw4626::new(&env);
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: true,
+        };
+        let _: Response = cw4626.instantiate(deps.as_mut(), msg).unwrap();
+        let start = env.block.height;
+
+        // Block 1: mint 1000 to the user
+        env.block.height = start + 1;
+        let _: Response = cw4626
+            .mint(deps.as_mut(), &env, &user, Uint128::from(1000u128))
+            .unwrap();
+
+        // Block 2: user sends 400 to the vault, which burns 100
+        env.block.height = start + 2;
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Transfer {
+                    recipient: cw4626.to_string(),
+                    amount: Uint128::from(400u128),
+                },
+            )
+            .unwrap();
+        let _: Response = cw4626
+            .burn(deps.as_mut(), &env, Uint128::from(100u128))
+            .unwrap();
+
+        // Block 3: vault transfers 300 back to the user
+        env.block.height = start + 3;
+        let _: Response = cw4626
+            .transfer(deps.as_mut(), &env, &user, Uint128::from(300u128))
+            .unwrap();
+
+        let query_at = |height: u64| {
+            (
+                cw4626
+                    .query_balance_at_height(deps.as_ref(), SENDER, height)
+                    .unwrap()
+                    .u128(),
+                cw4626
+                    .query_balance_at_height(deps.as_ref(), &cw4626.address, height)
+                    .unwrap()
+                    .u128(),
+                cw4626
+                    .query_total_supply_at_height(deps.as_ref(), height)
+                    .unwrap()
+                    .u128(),
+            )
+        };
+        // Values are at the start of each block
+        assert_eq!(query_at(start + 1), (0, 0, 0));
+        assert_eq!(query_at(start + 2), (1000, 0, 1000));
+        assert_eq!(query_at(start + 3), (600, 300, 900));
+        assert_eq!(query_at(start + 4), (900, 0, 900));
+    }
+
+    #[test]
+    fn test_snapshots_reject_non_normalized_addresses() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let cw4626 = Cw4626::new(&env);
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: true,
+        };
+        let _: Response = cw4626.instantiate(deps.as_mut(), msg).unwrap();
+        let _: Response = cw4626
+            .mint(
+                deps.as_mut(),
+                &env,
+                &Addr::unchecked(SENDER),
+                Uint128::from(1000u128),
+            )
+            .unwrap();
+
+        // The recipient is rejected before any balance or snapshot changes
+        let err = cw4626
+            .execute::<Empty, Empty>(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::Transfer {
+                    recipient: "Recipient".to_string(),
+                    amount: Uint128::from(100u128),
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            CwTokenError::Std(StdError::generic_err(
+                "Invalid input: address not normalized"
+            ))
+        );
+        assert_eq!(
+            cw4626
+                .query_balance_at_height(deps.as_ref(), SENDER, env.block.height + 1)
+                .unwrap(),
+            Uint128::from(1000u128)
+        );
+        assert_eq!(
+            cw4626
+                .query_balance_at_height(deps.as_ref(), "recipient", env.block.height + 1)
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn test_snapshots_disabled() {
+        let (deps, env, cw4626) = setup_and_mint(Uint128::from(1000u128), None);
+
+        let err = cw4626
+            .query_balance_at_height(deps.as_ref(), SENDER, env.block.height)
+            .unwrap_err();
+        assert_eq!(err, CwTokenError::SnapshotsDisabled {});
+        let err = cw4626
+            .query_total_supply_at_height(deps.as_ref(), env.block.height)
+            .unwrap_err();
+        assert_eq!(err, CwTokenError::SnapshotsDisabled {});
+    }
+
+    #[test]
+    fn test_mint_dead_shares() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let recipient = Addr::unchecked("recipient");
+        let cw4626 = Cw4626::new(&env);
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(1000u128),
+            }),
+            snapshots: false,
+        };
+        let _: Response = cw4626.instantiate(deps.as_mut(), msg).unwrap();
+
+        // First mint must be larger than the dead shares
+        Mint::<Empty>::mint(
+            &cw4626,
+            deps.as_mut(),
+            &env,
+            &recipient,
+            Uint128::from(1000u128),
+        )
+        .unwrap_err();
+
+        // Dead shares are minted to the contract on the first mint
+        let res: Response = cw4626
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(
+            res.events[0].attributes.last(),
+            Some(&attr("dead_shares", "1000"))
+        );
+        let balance = BALANCES.load(&deps.storage, &env.contract.address).unwrap();
+        assert_eq!(balance, Uint128::from(1000u128));
+        let balance = BALANCES.load(&deps.storage, &recipient).unwrap();
+        assert_eq!(balance, Uint128::from(4000u128));
+
+        // Only on the first mint
+        let _: Response = cw4626
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        let balance = BALANCES.load(&deps.storage, &env.contract.address).unwrap();
+        assert_eq!(balance, Uint128::from(1000u128));
+        let balance = BALANCES.load(&deps.storage, &recipient).unwrap();
+        assert_eq!(balance, Uint128::from(9000u128));
+
+        // Total supply includes the dead shares
+        let total_supply = cw4626.query_total_supply(deps.as_ref()).unwrap();
+        assert_eq!(total_supply, Uint128::from(10000u128));
+    }
+
+    #[test]
+    fn test_query() {
+        let owner = Addr::unchecked(SENDER);
+
+        // Setup and mint 1000 tokens to owner
+        let mint_amount = Uint128::from(1000u128);
+        let (mut deps, env, cw4626) = setup_and_mint(mint_amount, Some(&owner));
+
+        // Increase allowance of spender
+        let _: Response = cw4626
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(SENDER, &[]),
+                Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "spender".to_string(),
+                    amount: Uint128::from(400u128),
+                    expires: None,
+                },
+            )
+            .unwrap();
+
+        // Assert token info
+        let res: TokenInfoResponse = from_binary(
+            &cw4626
+                .query(deps.as_ref(), env.clone(), QueryMsg::TokenInfo {})
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            TokenInfoResponse {
+                name: "Cw4626 tokenized vault".to_string(),
+                symbol: "vaultToken".to_string(),
+                decimals: 6,
+                total_supply: mint_amount,
+            }
+        );
+
+        // Assert balance
+        let res: BalanceResponse = from_binary(
+            &cw4626
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::Balance {
+                        address: SENDER.to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.balance, mint_amount);
+
+        // Assert allowance
+        let res: AllowanceResponse = from_binary(
+            &cw4626
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::Allowance {
+                        owner: SENDER.to_string(),
+                        spender: "spender".to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.allowance, Uint128::from(400u128));
+
+        // Assert spender allowances
+        let res: AllSpenderAllowancesResponse = from_binary(
+            &cw4626
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::AllSpenderAllowances {
+                        spender: "spender".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.allowances.len(), 1);
+        assert_eq!(res.allowances[0].owner, SENDER);
+
+        // Assert all accounts
+        let res: AllAccountsResponse = from_binary(
+            &cw4626
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::AllAccounts {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.accounts, vec![SENDER.to_string()]);
+
+        // Assert there is no minter
+        let res: Option<MinterResponse> = from_binary(
+            &cw4626
+                .query(deps.as_ref(), env, QueryMsg::Minter {})
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res, None);
+    }
+
     #[test]
     fn test_to_string() {
         let cw4626 = Cw4626 {
             address: Addr::unchecked("cw4626"),
+            prefix: String::new(),
         };
 
         assert_eq!(cw4626.to_string(), "cw4626");
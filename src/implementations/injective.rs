@@ -0,0 +1,512 @@
+use crate::inflation::take_dead_shares;
+use crate::osmosis::{parse_factory_denom, ReceiveMode};
+use crate::{
+    Burn, CwTokenError, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate, Mint,
+    Query, Receive, SupplyBackend, Transfer,
+};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    attr, from_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, CustomQuery, Deps,
+    DepsMut, Env, Event, MessageInfo, Response, Uint128,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as CoinMsg;
+use prost::Message;
+use std::fmt::Display;
+
+// The messages below mirror `injective.tokenfactory.v1beta1` in
+// `proto/injective/tokenfactory/v1beta1/tx.proto` of injective-core v1.11,
+// where they only have the `sender` and `subdenom`/`amount` fields. Fields
+// added by later versions are omitted, which protobuf decodes as their
+// defaults.
+
+/// `MsgCreateDenom` of the Injective TokenFactory module. Creates the denom
+/// `factory/{sender}/{subdenom}`, with the sender as admin.
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgCreateDenom {
+    /// The creator of the denom
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    /// The subdenom of the new denom
+    #[prost(string, tag = "2")]
+    pub subdenom: String,
+}
+
+impl MsgCreateDenom {
+    /// The protobuf type URL of the message
+    pub const TYPE_URL: &'static str = "/injective.tokenfactory.v1beta1.MsgCreateDenom";
+}
+
+/// `MsgMint` of the Injective TokenFactory module. Mints `amount` to the
+/// sender, who must be the admin of the denom.
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgMint {
+    /// The admin of the denom, who receives the minted tokens
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    /// The amount to mint
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<CoinMsg>,
+}
+
+impl MsgMint {
+    /// The protobuf type URL of the message
+    pub const TYPE_URL: &'static str = "/injective.tokenfactory.v1beta1.MsgMint";
+}
+
+/// `MsgBurn` of the Injective TokenFactory module. Burns `amount` from the
+/// sender's balance.
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct MsgBurn {
+    /// The admin of the denom, whose tokens are burned
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    /// The amount to burn
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<CoinMsg>,
+}
+
+impl MsgBurn {
+    /// The protobuf type URL of the message
+    pub const TYPE_URL: &'static str = "/injective.tokenfactory.v1beta1.MsgBurn";
+}
+
+impl<T> From<MsgCreateDenom> for CosmosMsg<T> {
+    fn from(msg: MsgCreateDenom) -> Self {
+        stargate_msg(MsgCreateDenom::TYPE_URL, &msg)
+    }
+}
+
+impl<T> From<MsgMint> for CosmosMsg<T> {
+    fn from(msg: MsgMint) -> Self {
+        stargate_msg(MsgMint::TYPE_URL, &msg)
+    }
+}
+
+impl<T> From<MsgBurn> for CosmosMsg<T> {
+    fn from(msg: MsgBurn) -> Self {
+        stargate_msg(MsgBurn::TYPE_URL, &msg)
+    }
+}
+
+/// Encodes `msg` as a stargate message with `type_url`.
+fn stargate_msg<T>(type_url: &str, msg: &impl Message) -> CosmosMsg<T> {
+    CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: msg.encode_to_vec().into(),
+    }
+}
+
+#[cw_serde]
+/// Representation of a native token created using the Injective Token
+/// Factory. The denom of the token will be `factory/{owner}/{subdenom}`. If
+/// this token has not yet been created, the `instantiate` function must first
+/// be called and its response included in the transaction. Note that only the
+/// admin of the denom can mint or burn it.
+///
+/// Unlike on Osmosis, Injective's `MsgMint` always mints to the sender and
+/// `MsgBurn` always burns from the sender. [`Mint::mint`] therefore mints to
+/// the contract and sends the tokens on to the recipient, and there is no
+/// [`crate::BurnFrom`] implementation.
+///
+/// This struct implements the [`crate::VaultToken`] trait for any custom
+/// message and query type.
+pub struct InjectiveDenom {
+    /// Creator and admin of the denom. Only this address can mint and burn
+    /// tokens.
+    pub owner: String,
+    /// The subdenom of the token. All tokens created using the token factory
+    /// have the format `factory/{owner}/{subdenom}`.
+    pub subdenom: String,
+    /// How [`Receive::receive`] validates `info.funds`. Defaults to
+    /// [`ReceiveMode::Lenient`].
+    #[serde(default)]
+    pub receive_mode: ReceiveMode,
+    /// How [`Query::query_total_supply`] queries the total supply. Defaults
+    /// to [`SupplyBackend::Stargate`].
+    #[serde(default)]
+    pub supply_backend: SupplyBackend,
+}
+
+impl InjectiveDenom {
+    /// Creates a new [`InjectiveDenom`] obj instance
+    pub const fn new(owner: String, subdenom: String) -> Self {
+        Self {
+            owner,
+            subdenom,
+            receive_mode: ReceiveMode::Lenient,
+            supply_backend: SupplyBackend::Stargate,
+        }
+    }
+
+    /// Sets the [`ReceiveMode`] used by [`Receive::receive`].
+    pub const fn with_receive_mode(mut self, receive_mode: ReceiveMode) -> Self {
+        self.receive_mode = receive_mode;
+        self
+    }
+
+    /// Sets the [`SupplyBackend`] used by [`Query::query_total_supply`].
+    pub const fn with_supply_backend(mut self, supply_backend: SupplyBackend) -> Self {
+        self.supply_backend = supply_backend;
+        self
+    }
+
+    /// Create an [`InjectiveDenom`] from a string. `denom` must be the full
+    /// denom of the token, in the format `factory/{owner}/{subdenom}`.
+    ///
+    /// ## Errors
+    /// Will return [`CwTokenError::InvalidDenom`] if `denom` does not follow
+    /// the required format.
+    pub fn from_native_denom(denom: &str) -> CwTokenResult<Self> {
+        let (owner, subdenom) = parse_factory_denom(denom)?;
+        Ok(Self::new(owner, subdenom))
+    }
+
+    /// Returns a `MsgMint` message minting `amount` tokens to the contract.
+    fn mint_msg<C>(&self, env: &Env, amount: Uint128) -> CosmosMsg<C> {
+        MsgMint {
+            sender: env.contract.address.to_string(),
+            amount: Some(CoinMsg {
+                denom: self.to_string(),
+                amount: amount.to_string(),
+            }),
+        }
+        .into()
+    }
+}
+
+impl Display for InjectiveDenom {
+    /// Returns the full denom of the token, in the format
+    /// `factory/{owner}/{subdenom}`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "factory/{}/{}", self.owner, self.subdenom)
+    }
+}
+
+impl<Q: CustomQuery> Query<Q> for InjectiveDenom {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
+        Ok(deps
+            .querier
+            .query_balance(address, self.to_string())?
+            .amount)
+    }
+
+    /// Queries the total supply using the token's [`SupplyBackend`].
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        self.supply_backend
+            .query_total_supply(deps, self.to_string())
+    }
+
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        Ok(InflationProtection::load(deps.storage, &self.to_string())?)
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Mint<C, Q> for InjectiveDenom {
+    /// Mints `amount` tokens to the contract and sends them to the
+    /// `recipient`. If [`InflationProtection::DeadShares`] is configured, the
+    /// dead shares are kept by the contract out of `amount` on the first mint.
+    fn mint(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let mut event = Event::new("apollo/cw-vault-token/injective").add_attributes(vec![
+            attr("action", "mint"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        // lock dead shares in the contract on the first mint, if configured
+        let dead_shares = take_dead_shares(deps.storage, &self.to_string(), amount)?;
+        self.supply_backend
+            .record_mint(deps.storage, &self.to_string(), amount)?;
+        if !dead_shares.is_zero() {
+            event = event.add_attribute("dead_shares", dead_shares.to_string());
+        }
+
+        let mut res = Response::new().add_message(self.mint_msg(env, amount));
+        if *recipient != env.contract.address {
+            res = res.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: self.to_string(),
+                    amount: amount - dead_shares,
+                }],
+            });
+        }
+
+        Ok(res.add_event(event))
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Burn<C, Q> for InjectiveDenom {
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.supply_backend
+            .record_burn(deps.storage, &self.to_string(), amount)?;
+
+        let event = Event::new("apollo/cw-vault-token/injective").add_attributes(vec![
+            attr("action", "burn"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_message(MsgBurn {
+                sender: env.contract.address.to_string(),
+                amount: Some(CoinMsg {
+                    denom: self.to_string(),
+                    amount: amount.to_string(),
+                }),
+            })
+            .add_event(event))
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for InjectiveDenom {
+    fn transfer(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let send_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: self.to_string(),
+                amount,
+            }],
+        };
+
+        let event = Event::new("apollo/cw-vault-token/injective").add_attributes(vec![
+            attr("action", "transfer"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(send_msg).add_event(event))
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+/// Optional instantiate message for an [`InjectiveDenom`].
+pub struct InjectiveDenomInstantiateMsg {
+    /// Optional protection against the inflation attack. Defaults to
+    /// [`InflationProtection::None`].
+    pub inflation_protection: Option<InflationProtection>,
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Instantiate<C, Q> for InjectiveDenom {
+    type InitInfo = InjectiveDenomInstantiateMsg;
+
+    fn instantiate(
+        &self,
+        deps: DepsMut<Q>,
+        msg: InjectiveDenomInstantiateMsg,
+    ) -> CwTokenResponse<C> {
+        if let Some(inflation_protection) = &msg.inflation_protection {
+            inflation_protection.save(deps.storage, &self.to_string())?;
+        }
+
+        let init_msg = MsgCreateDenom {
+            sender: self.owner.clone(),
+            subdenom: self.subdenom.clone(),
+        };
+
+        let init_event =
+            Event::new("apollo/cw-token/instantiate").add_attribute("denom", self.to_string());
+        Ok(Response::new().add_message(init_msg).add_event(init_event))
+    }
+
+    /// Instantiate from binary encoded [`InjectiveDenomInstantiateMsg`].
+    /// Since all fields are optional, `init_info` may be `None`.
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        let msg = init_info
+            .map(|init_info| from_binary(&init_info))
+            .transpose()?
+            .unwrap_or_default();
+        self.instantiate(deps, msg)
+    }
+}
+
+impl<Q: CustomQuery> Receive<Q> for InjectiveDenom {
+    /// Validates that `amount` tokens were sent in `info.funds`, according to
+    /// the [`ReceiveMode`] of the token, and returns the leftover funds.
+    fn receive(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        info: &MessageInfo,
+        amount: Uint128,
+    ) -> CwTokenResult<Vec<Coin>> {
+        self.receive_mode
+            .receive(self.to_string(), &info.funds, amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::Empty;
+    use test_case::test_case;
+
+    use super::*;
+
+    const SUBDENOM: &str = "subdenom";
+
+    #[test_case("factory/owner/subdenom" ; "valid denom")]
+    #[test_case("factory/owner" => panics ; "denom contains too few parts")]
+    #[test_case("factory/owner/subdenom/extra" => panics ; "denom contains too many parts")]
+    #[test_case("peggy/owner/subdenom" => panics ; "denom does not start with \"factory\"")]
+    fn from_native_denom(denom: &str) {
+        let denom = InjectiveDenom::from_native_denom(denom).unwrap();
+        assert_eq!(denom.owner, "owner");
+        assert_eq!(denom.subdenom, SUBDENOM);
+        assert_eq!(denom.to_string(), "factory/owner/subdenom");
+    }
+
+    #[test]
+    fn instantiate() {
+        let mut deps = mock_dependencies();
+        let denom = InjectiveDenom::new("owner".to_string(), SUBDENOM.to_string());
+
+        let res: Response = denom.instantiate_binary(deps.as_mut(), None).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Stargate { type_url, value } => {
+                assert_eq!(type_url, MsgCreateDenom::TYPE_URL);
+                assert_eq!(
+                    MsgCreateDenom::decode(value.as_slice()).unwrap(),
+                    MsgCreateDenom {
+                        sender: "owner".to_string(),
+                        subdenom: SUBDENOM.to_string(),
+                    }
+                );
+            }
+            msg => panic!("unexpected message {msg:?}"),
+        }
+    }
+
+    #[test]
+    fn mint_msg_matches_proto_wire_format() {
+        let mut env = mock_env();
+        env.contract.address = Addr::unchecked("inj1sender");
+        let denom = InjectiveDenom::new("inj1sender".to_string(), "vault".to_string());
+
+        // The protobuf wire format of `MsgMint{sender: "inj1sender", amount:
+        // {denom: "factory/inj1sender/vault", amount: "1000"}}` according to
+        // the v1.11 proto: field 1 is the sender string, field 2 the embedded
+        // coin with its denom (1) and amount (2). Checks the field numbers and
+        // types of the prost structs against the proto, not against bytes
+        // produced by injectived.
+        let expected: Vec<u8> = [
+            &[0x0a, 0x0a][..],
+            b"inj1sender",
+            &[0x12, 0x20, 0x0a, 0x18],
+            b"factory/inj1sender/vault",
+            &[0x12, 0x04],
+            b"1000",
+        ]
+        .concat();
+
+        match denom.mint_msg::<Empty>(&env, Uint128::from(1000u128)) {
+            CosmosMsg::Stargate { type_url, value } => {
+                assert_eq!(type_url, "/injective.tokenfactory.v1beta1.MsgMint");
+                assert_eq!(value.to_vec(), expected);
+            }
+            msg => panic!("unexpected message {msg:?}"),
+        }
+    }
+
+    #[test]
+    fn mint_sends_to_recipient() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = InjectiveDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let init_info = InjectiveDenomInstantiateMsg {
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(1000u128),
+            }),
+        };
+        let _: Response = denom.instantiate(deps.as_mut(), init_info).unwrap();
+
+        // The full amount is minted to the contract, which keeps the dead shares
+        let res: Response = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                denom.mint_msg(&env, Uint128::from(5000u128)),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![Coin::new(4000, denom.to_string())],
+                }),
+            ]
+        );
+
+        // Only the mint is needed when minting to the contract itself
+        let res: Response = denom
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::from(5000u128),
+            )
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            denom.mint_msg(&env, Uint128::from(5000u128))
+        );
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn burn() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = InjectiveDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        let res: Response = denom
+            .burn(deps.as_mut(), &env, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            MsgBurn {
+                sender: env.contract.address.to_string(),
+                amount: Some(CoinMsg {
+                    denom: denom.to_string(),
+                    amount: "1000".to_string(),
+                }),
+            }
+            .into()
+        );
+
+        let err = Burn::<Empty>::burn(&denom, deps.as_mut(), &env, Uint128::zero()).unwrap_err();
+        assert_eq!(err, CwTokenError::ZeroAmount {});
+    }
+}
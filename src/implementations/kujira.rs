@@ -0,0 +1,696 @@
+use crate::inflation::take_dead_shares;
+use crate::neutron::into_custom;
+use crate::osmosis::{parse_factory_denom, ReceiveMode};
+use crate::{
+    Burn, CwTokenError, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate, Mint,
+    Query, Receive, SupplyBackend, Transfer,
+};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_std::{
+    attr, from_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, CustomQuery, Deps,
+    DepsMut, Env, Event, MessageInfo, Response, StdResult, Uint128,
+};
+use std::fmt::Display;
+
+#[cw_serde]
+/// The custom messages of Kujira's `KujiraMsg` bindings used by
+/// [`KujiraDenom`]. They serialize exactly like the matching variants of
+/// `kujira::msg::KujiraMsg`, so a contract can use either this type or
+/// `kujira::msg::KujiraMsg` as its custom message type. See [`into_custom`]
+/// for how they are converted.
+pub enum KujiraMsg {
+    /// Messages of Kujira's denom module
+    Denom(DenomMsg),
+}
+
+impl CustomMsg for KujiraMsg {}
+
+impl From<DenomMsg> for KujiraMsg {
+    fn from(msg: DenomMsg) -> Self {
+        Self::Denom(msg)
+    }
+}
+
+#[cw_serde]
+/// The messages of Kujira's denom module used by [`KujiraDenom`]. They
+/// serialize exactly like the matching variants of `kujira::msg::DenomMsg`.
+pub enum DenomMsg {
+    /// Creates the denom `factory/{sender}/{subdenom}`, with the sender as
+    /// admin. The creation fee of the module is deducted from the sender's
+    /// balance.
+    Create {
+        /// The subdenom of the new denom
+        subdenom: String,
+    },
+    /// Mints `amount` tokens of `denom` to `recipient`. Only the admin of the
+    /// denom can mint.
+    Mint {
+        /// The full denom of the token
+        denom: String,
+        /// The amount to mint
+        amount: Uint128,
+        /// The recipient of the minted tokens
+        recipient: Addr,
+    },
+    /// Burns `amount` tokens of `denom` from the sender's balance. Only the
+    /// admin of the denom can burn.
+    Burn {
+        /// The full denom of the token
+        denom: String,
+        /// The amount to burn
+        amount: Uint128,
+    },
+}
+
+#[cw_serde]
+/// Representation of a native token created using Kujira's denom module. The
+/// denom of the token will be `factory/{owner}/{subdenom}`. The module is
+/// driven by [`KujiraMsg`] custom messages, so the trait implementations
+/// return responses with any custom message type that a [`KujiraMsg`] can be
+/// converted into. If this token has not yet been created, the `instantiate`
+/// function must first be called and its response included in the
+/// transaction. Creating a denom costs a fee, see
+/// [`KujiraDenomInstantiateMsg::creation_fee`]. Note that only the admin of
+/// the denom can mint or burn it.
+///
+/// This struct implements the [`crate::VaultToken`] trait for any custom
+/// message type that can represent [`KujiraMsg`], see [`into_custom`], and
+/// any custom query type.
+pub struct KujiraDenom {
+    /// Creator and admin of the denom. Only this address can mint and burn
+    /// tokens.
+    pub owner: String,
+    /// The subdenom of the token. All tokens created using the denom module
+    /// have the format `factory/{owner}/{subdenom}`.
+    pub subdenom: String,
+    /// How [`Receive::receive`] validates `info.funds`. Defaults to
+    /// [`ReceiveMode::Lenient`].
+    #[serde(default)]
+    pub receive_mode: ReceiveMode,
+    /// How [`Query::query_total_supply`] queries the total supply. Defaults
+    /// to [`SupplyBackend::Stargate`].
+    #[serde(default)]
+    pub supply_backend: SupplyBackend,
+}
+
+impl KujiraDenom {
+    /// Creates a new [`KujiraDenom`] obj instance
+    pub const fn new(owner: String, subdenom: String) -> Self {
+        Self {
+            owner,
+            subdenom,
+            receive_mode: ReceiveMode::Lenient,
+            supply_backend: SupplyBackend::Stargate,
+        }
+    }
+
+    /// Sets the [`ReceiveMode`] used by [`Receive::receive`].
+    pub const fn with_receive_mode(mut self, receive_mode: ReceiveMode) -> Self {
+        self.receive_mode = receive_mode;
+        self
+    }
+
+    /// Sets the [`SupplyBackend`] used by [`Query::query_total_supply`].
+    pub const fn with_supply_backend(mut self, supply_backend: SupplyBackend) -> Self {
+        self.supply_backend = supply_backend;
+        self
+    }
+
+    /// Create a [`KujiraDenom`] from a string. `denom` must be the full denom
+    /// of the token, in the format `factory/{owner}/{subdenom}`.
+    ///
+    /// ## Errors
+    /// Will return [`CwTokenError::InvalidDenom`] if `denom` does not follow
+    /// the required format.
+    pub fn from_native_denom(denom: &str) -> CwTokenResult<Self> {
+        let (owner, subdenom) = parse_factory_denom(denom)?;
+        Ok(Self::new(owner, subdenom))
+    }
+
+    /// Wraps a [`DenomMsg`] into a custom message of type `C`.
+    fn custom_msg<C: DeserializeOwned + Serialize>(msg: DenomMsg) -> StdResult<CosmosMsg<C>> {
+        Ok(CosmosMsg::Custom(into_custom(&KujiraMsg::Denom(msg))?))
+    }
+
+    /// Returns a [`DenomMsg::Mint`] message minting `amount` tokens to
+    /// `recipient`.
+    fn mint_msg<C: DeserializeOwned + Serialize>(
+        &self,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg<C>> {
+        Self::custom_msg(DenomMsg::Mint {
+            denom: self.to_string(),
+            amount,
+            recipient: recipient.clone(),
+        })
+    }
+
+    /// ## Description
+    /// Like [`Instantiate::instantiate`], but first checks that the denom is
+    /// created by the contract and that the contract can pay
+    /// [`KujiraDenomInstantiateMsg::creation_fee`], failing early with a clear
+    /// error instead of in the denom module. The module deducts the fee from
+    /// the balance of the contract sending the create message, i.e.
+    /// `env.contract.address`.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::Unauthorized`] if [`KujiraDenom::owner`] is not
+    /// the contract, since the created denom would not match the token, and
+    /// [`CwTokenError::InsufficientFunds`] if the contract's balance does not
+    /// cover the creation fee.
+    pub fn instantiate_with_env<C, Q>(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        msg: KujiraDenomInstantiateMsg,
+    ) -> CwTokenResponse<C>
+    where
+        C: CustomMsg + DeserializeOwned,
+        Q: CustomQuery,
+    {
+        if self.owner != env.contract.address.as_str() {
+            return Err(CwTokenError::Unauthorized {});
+        }
+        for fee in msg.creation_fee.iter().filter(|fee| !fee.amount.is_zero()) {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &fee.denom)?;
+            if balance.amount < fee.amount {
+                return Err(CwTokenError::InsufficientFunds {
+                    expected: fee.clone(),
+                    received: balance,
+                });
+            }
+        }
+
+        self.instantiate(deps, msg)
+    }
+}
+
+impl Display for KujiraDenom {
+    /// Returns the full denom of the token, in the format
+    /// `factory/{owner}/{subdenom}`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "factory/{}/{}", self.owner, self.subdenom)
+    }
+}
+
+impl<Q: CustomQuery> Query<Q> for KujiraDenom {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
+        Ok(deps
+            .querier
+            .query_balance(address, self.to_string())?
+            .amount)
+    }
+
+    /// Queries the total supply using the token's [`SupplyBackend`].
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        self.supply_backend
+            .query_total_supply(deps, self.to_string())
+    }
+
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        Ok(InflationProtection::load(deps.storage, &self.to_string())?)
+    }
+}
+
+impl<C, Q> Mint<C, Q> for KujiraDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery,
+{
+    /// Mint tokens to the `recipient`. If [`InflationProtection::DeadShares`]
+    /// is configured, the dead shares are minted to the contract out of
+    /// `amount` on the first mint.
+    fn mint(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let mut res = Response::new();
+        let mut event = Event::new("apollo/cw-vault-token/kujira").add_attributes(vec![
+            attr("action", "mint"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        // lock dead shares in the contract on the first mint, if configured
+        let dead_shares = take_dead_shares(deps.storage, &self.to_string(), amount)?;
+        self.supply_backend
+            .record_mint(deps.storage, &self.to_string(), amount)?;
+        if !dead_shares.is_zero() {
+            res = res.add_message(self.mint_msg(&env.contract.address, dead_shares)?);
+            event = event.add_attribute("dead_shares", dead_shares.to_string());
+        }
+
+        Ok(res
+            .add_message(self.mint_msg(recipient, amount - dead_shares)?)
+            .add_event(event))
+    }
+}
+
+impl<C, Q> Burn<C, Q> for KujiraDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery,
+{
+    fn burn(&self, deps: DepsMut<Q>, _env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.supply_backend
+            .record_burn(deps.storage, &self.to_string(), amount)?;
+
+        let burn_msg = Self::custom_msg(DenomMsg::Burn {
+            denom: self.to_string(),
+            amount,
+        })?;
+
+        let event = Event::new("apollo/cw-vault-token/kujira").add_attributes(vec![
+            attr("action", "burn"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+        ]);
+        Ok(Response::new().add_message(burn_msg).add_event(event))
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for KujiraDenom {
+    fn transfer(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let send_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: self.to_string(),
+                amount,
+            }],
+        };
+
+        let event = Event::new("apollo/cw-vault-token/kujira").add_attributes(vec![
+            attr("action", "transfer"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(send_msg).add_event(event))
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+/// Instantiate message for a [`KujiraDenom`].
+pub struct KujiraDenomInstantiateMsg {
+    /// The fee charged by the denom module for creating a denom. The module
+    /// deducts it from the contract's balance, so the contract must hold at
+    /// least this amount when the denom is created, e.g. by requiring it in
+    /// the funds of its own instantiate message. Defaults to no fee. Checked
+    /// against the contract's balance by
+    /// [`KujiraDenom::instantiate_with_env`].
+    #[serde(default)]
+    pub creation_fee: Vec<Coin>,
+    /// Optional protection against the inflation attack. Defaults to
+    /// [`InflationProtection::None`].
+    pub inflation_protection: Option<InflationProtection>,
+}
+
+impl<C, Q> Instantiate<C, Q> for KujiraDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery,
+{
+    type InitInfo = KujiraDenomInstantiateMsg;
+
+    /// Creates the denom. Note that the denom is always created by the
+    /// contract, so [`KujiraDenom::owner`] must be the contract's address.
+    ///
+    /// The denom module deducts [`KujiraDenomInstantiateMsg::creation_fee`]
+    /// from the contract's balance when executing the create message, so the
+    /// fee is not part of the returned messages. Without an [`Env`], the
+    /// contract and its balance are unknown, so neither is checked here. Use
+    /// [`KujiraDenom::instantiate_with_env`] to check them.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError`] if `msg` contains an invalid
+    /// [`InflationProtection`], or if `C` can't represent [`KujiraMsg`].
+    fn instantiate(&self, deps: DepsMut<Q>, msg: KujiraDenomInstantiateMsg) -> CwTokenResponse<C> {
+        if let Some(inflation_protection) = &msg.inflation_protection {
+            inflation_protection.save(deps.storage, &self.to_string())?;
+        }
+
+        let init_msg = Self::custom_msg(DenomMsg::Create {
+            subdenom: self.subdenom.clone(),
+        })?;
+
+        let mut init_event =
+            Event::new("apollo/cw-token/instantiate").add_attribute("denom", self.to_string());
+        if !msg.creation_fee.is_empty() {
+            let fee = msg
+                .creation_fee
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            init_event = init_event.add_attribute("creation_fee", fee);
+        }
+        Ok(Response::new().add_message(init_msg).add_event(init_event))
+    }
+
+    /// Instantiate from binary encoded [`KujiraDenomInstantiateMsg`]. Since
+    /// all fields are optional, `init_info` may be `None`.
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        let msg = init_info
+            .map(|init_info| from_binary(&init_info))
+            .transpose()?
+            .unwrap_or_default();
+        self.instantiate(deps, msg)
+    }
+}
+
+impl<Q: CustomQuery> Receive<Q> for KujiraDenom {
+    /// Validates that `amount` tokens were sent in `info.funds`, according to
+    /// the [`ReceiveMode`] of the token, and returns the leftover funds.
+    fn receive(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        info: &MessageInfo,
+        amount: Uint128,
+    ) -> CwTokenResult<Vec<Coin>> {
+        self.receive_mode
+            .receive(self.to_string(), &info.funds, amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies_with_balances, mock_env};
+    use cosmwasm_std::to_binary;
+    use test_case::test_case;
+
+    use super::*;
+
+    const SUBDENOM: &str = "subdenom";
+
+    #[test]
+    fn to_string() {
+        let denom = KujiraDenom::new("owner".to_string(), SUBDENOM.to_string());
+        assert_eq!(denom.to_string(), "factory/owner/subdenom");
+        assert_eq!(
+            KujiraDenom::from_native_denom("factory/owner/subdenom").unwrap(),
+            denom
+        );
+        assert!(KujiraDenom::from_native_denom("factory/owner").is_err());
+    }
+
+    #[test_case(vec![] => matches Ok(_) ; "no fee")]
+    #[test_case(vec![Coin::new(10_000_000, "ukuji")] => matches Ok(_) ; "fee covered by balance")]
+    #[test_case(vec![Coin::new(0, "uusk")] => matches Ok(_) ; "zero fee is ignored")]
+    #[test_case(vec![Coin::new(10_000_001, "ukuji")] => matches Err(CwTokenError::InsufficientFunds { .. }) ; "fee exceeds balance")]
+    #[test_case(vec![Coin::new(1, "uusk")] => matches Err(CwTokenError::InsufficientFunds { .. }) ; "no balance of fee denom")]
+    fn instantiate_creation_fee(creation_fee: Vec<Coin>) -> CwTokenResponse<KujiraMsg> {
+        let env = mock_env();
+        // The owner's balance is irrelevant, the fee is paid by the contract
+        let mut deps = mock_dependencies_with_balances(&[
+            (
+                env.contract.address.as_str(),
+                &[Coin::new(10_000_000, "ukuji")],
+            ),
+            ("owner", &[Coin::new(1, "uusk")]),
+        ]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        let res = denom.instantiate_with_env(
+            deps.as_mut(),
+            &env,
+            KujiraDenomInstantiateMsg {
+                creation_fee,
+                inflation_protection: None,
+            },
+        )?;
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Create {
+                subdenom: SUBDENOM.to_string(),
+            }))
+        );
+        Ok(res)
+    }
+
+    #[test]
+    fn instantiate_with_env_rejects_other_owner() {
+        let env = mock_env();
+        let mut deps =
+            mock_dependencies_with_balances(&[("owner", &[Coin::new(10_000_000, "ukuji")])]);
+        let denom = KujiraDenom::new("owner".to_string(), SUBDENOM.to_string());
+
+        let err = denom
+            .instantiate_with_env::<KujiraMsg, _>(
+                deps.as_mut(),
+                &env,
+                KujiraDenomInstantiateMsg {
+                    creation_fee: vec![Coin::new(10_000_000, "ukuji")],
+                    inflation_protection: None,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(err, CwTokenError::Unauthorized {});
+    }
+
+    #[test]
+    fn creation_fee_is_paid_by_the_chain() {
+        let env = mock_env();
+        let mut deps = mock_dependencies_with_balances(&[(
+            env.contract.address.as_str(),
+            &[Coin::new(10_000_000, "ukuji")],
+        )]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        let res: Response<KujiraMsg> = denom
+            .instantiate(
+                deps.as_mut(),
+                KujiraDenomInstantiateMsg {
+                    creation_fee: vec![Coin::new(10_000_000, "ukuji")],
+                    inflation_protection: None,
+                },
+            )
+            .unwrap();
+
+        // The create message is the only message, the module deducts the fee
+        // from the contract's balance when executing it
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Create {
+                subdenom: SUBDENOM.to_string(),
+            }))]
+        );
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("denom", denom.to_string()),
+                attr("creation_fee", "10000000ukuji"),
+            ]
+        );
+    }
+
+    #[test]
+    fn instantiate_and_mint_dead_shares() {
+        let env = mock_env();
+        let mut deps = mock_dependencies_with_balances(&[]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let init_info = KujiraDenomInstantiateMsg {
+            creation_fee: vec![],
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(1000u128),
+            }),
+        };
+        let _: Response<KujiraMsg> = denom.instantiate(deps.as_mut(), init_info).unwrap();
+
+        // Dead shares are minted to the contract on the first mint
+        let res: Response<KujiraMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Mint {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(1000u128),
+                    recipient: env.contract.address.clone(),
+                })),
+                CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Mint {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(4000u128),
+                    recipient,
+                })),
+            ]
+        );
+    }
+
+    #[test]
+    fn burn() {
+        let env = mock_env();
+        let mut deps = mock_dependencies_with_balances(&[]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let amount = Uint128::from(1000u128);
+
+        let res: Response<KujiraMsg> = denom.burn(deps.as_mut(), &env, amount).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(KujiraMsg::Denom(DenomMsg::Burn {
+                denom: denom.to_string(),
+                amount,
+            }))
+        );
+
+        let err =
+            Burn::<KujiraMsg, _>::burn(&denom, deps.as_mut(), &env, Uint128::zero()).unwrap_err();
+        assert_eq!(err, CwTokenError::ZeroAmount {});
+    }
+
+    #[test]
+    fn serializes_like_kujira_bindings() {
+        let msg = KujiraMsg::Denom(DenomMsg::Mint {
+            denom: "factory/owner/subdenom".to_string(),
+            amount: Uint128::from(1000u128),
+            recipient: Addr::unchecked("recipient"),
+        });
+        assert_eq!(
+            to_binary(&msg).unwrap(),
+            Binary::from(
+                br#"{"denom":{"mint":{"denom":"factory/owner/subdenom","amount":"1000","recipient":"recipient"}}}"#
+                    .as_slice()
+            )
+        );
+    }
+
+    /// Stand-in for `kujira::msg::KujiraMsg`, which has more variants than
+    /// [`KujiraMsg`] and can't implement `From<KujiraMsg>` in a downstream
+    /// contract due to the orphan rule.
+    #[cw_serde]
+    enum SdkKujiraMsg {
+        Auth(SdkAuthMsg),
+        Denom(SdkDenomMsg),
+    }
+
+    impl CustomMsg for SdkKujiraMsg {}
+
+    /// Stand-in for `kujira::msg::AuthMsg`.
+    #[cw_serde]
+    enum SdkAuthMsg {
+        CreateVestingAccount { to_address: String },
+    }
+
+    /// Stand-in for `kujira::msg::DenomMsg`.
+    #[cw_serde]
+    enum SdkDenomMsg {
+        Create {
+            subdenom: String,
+        },
+        ChangeAdmin {
+            denom: String,
+            address: Addr,
+        },
+        Mint {
+            denom: String,
+            amount: Uint128,
+            recipient: Addr,
+        },
+        Burn {
+            denom: String,
+            amount: Uint128,
+        },
+    }
+
+    #[test]
+    fn converts_into_sdk_types() {
+        let env = mock_env();
+        let mut deps = mock_dependencies_with_balances(&[]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let res: Response<SdkKujiraMsg> = denom
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkKujiraMsg::Denom(SdkDenomMsg::Create {
+                subdenom: SUBDENOM.to_string(),
+            }))
+        );
+        let res: Response<SdkKujiraMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkKujiraMsg::Denom(SdkDenomMsg::Mint {
+                denom: denom.to_string(),
+                amount: Uint128::from(1000u128),
+                recipient,
+            }))
+        );
+        let res: Response<SdkKujiraMsg> = denom
+            .burn(deps.as_mut(), &env, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkKujiraMsg::Denom(SdkDenomMsg::Burn {
+                denom: denom.to_string(),
+                amount: Uint128::from(1000u128),
+            }))
+        );
+    }
+
+    #[test]
+    fn rejects_types_that_cant_represent_kujira_msgs() {
+        let env = mock_env();
+        let mut deps = mock_dependencies_with_balances(&[]);
+        let denom = KujiraDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        // `Empty` deserializes from any JSON object, but can't serialize back
+        let err = Mint::<cosmwasm_std::Empty, _>::mint(
+            &denom,
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("recipient"),
+            Uint128::from(1000u128),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CwTokenError::Std(cosmwasm_std::StdError::ParseErr { .. })
+        ));
+    }
+}
@@ -1,5 +1,21 @@
+/// Runtime dispatch over the implementations
+pub mod any;
+
+/// Osmosis tokenFactory before send hooks
+pub mod before_send;
+
 /// CW4626 standard tokenized vaults
 pub mod cw4626;
 
+/// Injective tokenFactory Denom
+#[cfg(feature = "injective")]
+pub mod injective;
+
+/// Kujira denom module Denom
+pub mod kujira;
+
+/// Neutron tokenFactory Denom
+pub mod neutron;
+
 /// Osmosis tokenFactory Denom
 pub mod osmosis;
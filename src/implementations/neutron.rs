@@ -0,0 +1,773 @@
+use crate::inflation::take_dead_shares;
+use crate::osmosis::{parse_factory_denom, ReceiveMode};
+use crate::{
+    Burn, CwTokenError, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate, Mint,
+    Query, Receive, SupplyBackend, Transfer,
+};
+
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, CustomQuery,
+    Deps, DepsMut, Env, Event, MessageInfo, QueryRequest, Response, StdError, StdResult, Uint128,
+};
+use std::any::type_name;
+use std::fmt::Display;
+
+#[cw_serde]
+/// The token factory messages of Neutron's custom `NeutronMsg` bindings used by
+/// [`NeutronDenom`]. They serialize exactly like the matching variants of
+/// `neutron_sdk::bindings::msg::NeutronMsg`, so a contract can either use this
+/// type as its custom message type, or the SDK's type. See
+/// [`into_custom`] for how they are converted.
+pub enum NeutronMsg {
+    /// Creates the denom `factory/{sender}/{subdenom}`, with the sender as
+    /// admin.
+    CreateDenom {
+        /// The subdenom of the new denom
+        subdenom: String,
+    },
+    /// Mints `amount` tokens of `denom` to `mint_to_address`. Only the admin
+    /// of the denom can mint.
+    MintTokens {
+        /// The full denom of the token
+        denom: String,
+        /// The amount to mint
+        amount: Uint128,
+        /// The recipient of the minted tokens
+        mint_to_address: String,
+    },
+    /// Burns `amount` tokens of `denom` from the sender's balance. Only the
+    /// admin of the denom can burn.
+    BurnTokens {
+        /// The full denom of the token
+        denom: String,
+        /// The amount to burn
+        amount: Uint128,
+        /// Must be empty, burning from other addresses is not supported
+        burn_from_address: String,
+    },
+}
+
+impl CustomMsg for NeutronMsg {}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+/// The token factory queries of Neutron's custom `NeutronQuery` bindings used
+/// by [`NeutronDenom`]. They serialize exactly like the matching variants of
+/// `neutron_sdk::bindings::query::NeutronQuery`.
+pub enum NeutronQuery {
+    /// Returns the admin of a denom.
+    #[returns(DenomAdminResponse)]
+    DenomAdmin {
+        /// The full denom of the token
+        subdenom: String,
+    },
+}
+
+impl CustomQuery for NeutronQuery {}
+
+#[cw_serde]
+/// Response to [`NeutronQuery::DenomAdmin`].
+pub struct DenomAdminResponse {
+    /// The admin of the denom
+    pub admin: String,
+}
+
+#[cw_serde]
+/// Representation of a native token created using the Neutron Token Factory.
+/// The denom of the token will be `factory/{owner}/{subdenom}`. The token
+/// factory is driven by [`NeutronMsg`] custom messages, so the trait
+/// implementations return responses with any custom message type that a
+/// [`NeutronMsg`] can be converted into. If this token has not yet been
+/// created, the `instantiate` function must first be called and its response
+/// included in the transaction. Note that only the admin of the denom can
+/// mint or burn it.
+///
+/// This struct implements the [`crate::VaultToken`] trait for any custom
+/// message type that can represent a [`NeutronMsg`], such as [`NeutronMsg`]
+/// itself or `neutron_sdk::bindings::msg::NeutronMsg`, and any custom query
+/// type. See [`into_custom`].
+pub struct NeutronDenom {
+    /// Creator and admin of the denom. Only this address can mint and burn
+    /// tokens.
+    pub owner: String,
+    /// The subdenom of the token. All tokens created using the token factory
+    /// have the format `factory/{owner}/{subdenom}`.
+    pub subdenom: String,
+    /// How [`Receive::receive`] validates `info.funds`. Defaults to
+    /// [`ReceiveMode::Lenient`].
+    #[serde(default)]
+    pub receive_mode: ReceiveMode,
+    /// How [`Query::query_total_supply`] queries the total supply. Defaults
+    /// to [`SupplyBackend::Stargate`].
+    #[serde(default)]
+    pub supply_backend: SupplyBackend,
+    /// Whether mint and burn first check that the contract is the admin of
+    /// the denom, returning [`CwTokenError::NotDenomAdmin`] instead of a
+    /// failing message. Requires the custom query type to represent
+    /// [`NeutronQuery::DenomAdmin`]. Defaults to `false`.
+    #[serde(default)]
+    pub check_admin: bool,
+}
+
+/// ## Description
+/// Converts `value` into the custom message or query type `T` of a contract
+/// through their JSON encoding. The orphan rule prevents contracts from
+/// implementing `From` between the binding types of this crate and those of
+/// chain SDKs such as `neutron_sdk` or `kujira`, so instead `T` must deserialize from the
+/// same JSON as `value` and serialize back to it.
+///
+/// ## Errors
+/// Returns [`StdError::ParseErr`] if `T` can't represent `value`, e.g. if `T`
+/// is `Empty`.
+pub fn into_custom<M, T>(value: &M) -> StdResult<T>
+where
+    M: Serialize + DeserializeOwned + PartialEq,
+    T: Serialize + DeserializeOwned,
+{
+    let converted: T = from_binary(&to_binary(value)?)?;
+    match from_binary::<M>(&to_binary(&converted)?) {
+        Ok(round_trip) if round_trip == *value => Ok(converted),
+        _ => Err(StdError::parse_err(
+            type_name::<T>(),
+            format!("can't represent {}", type_name::<M>()),
+        )),
+    }
+}
+
+impl NeutronDenom {
+    /// Creates a new [`NeutronDenom`] obj instance
+    pub const fn new(owner: String, subdenom: String) -> Self {
+        Self {
+            owner,
+            subdenom,
+            receive_mode: ReceiveMode::Lenient,
+            supply_backend: SupplyBackend::Stargate,
+            check_admin: false,
+        }
+    }
+
+    /// Enables or disables the admin check of mint and burn, see
+    /// [`NeutronDenom::check_admin`].
+    pub const fn with_admin_check(mut self, check_admin: bool) -> Self {
+        self.check_admin = check_admin;
+        self
+    }
+
+    /// Sets the [`ReceiveMode`] used by [`Receive::receive`].
+    pub const fn with_receive_mode(mut self, receive_mode: ReceiveMode) -> Self {
+        self.receive_mode = receive_mode;
+        self
+    }
+
+    /// Sets the [`SupplyBackend`] used by [`Query::query_total_supply`].
+    pub const fn with_supply_backend(mut self, supply_backend: SupplyBackend) -> Self {
+        self.supply_backend = supply_backend;
+        self
+    }
+
+    /// Create a [`NeutronDenom`] from a string. `denom` must be the full denom
+    /// of the token, in the format `factory/{owner}/{subdenom}`.
+    ///
+    /// ## Errors
+    /// Will return [`CwTokenError::InvalidDenom`] if `denom` does not follow
+    /// the required format.
+    pub fn from_native_denom(denom: &str) -> CwTokenResult<Self> {
+        let (owner, subdenom) = parse_factory_denom(denom)?;
+        Ok(Self::new(owner, subdenom))
+    }
+
+    /// ## Description
+    /// Queries the admin of the denom from the token factory, using
+    /// [`NeutronQuery::DenomAdmin`].
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the query fails or if `Q` can't represent
+    /// [`NeutronQuery::DenomAdmin`].
+    pub fn query_admin<Q: CustomQuery + DeserializeOwned>(
+        &self,
+        deps: Deps<Q>,
+    ) -> CwTokenResult<String> {
+        let query: Q = into_custom(&NeutronQuery::DenomAdmin {
+            subdenom: self.to_string(),
+        })?;
+        let res: DenomAdminResponse = deps.querier.query(&QueryRequest::Custom(query))?;
+        Ok(res.admin)
+    }
+
+    /// Checks that the contract is the admin of the denom, if
+    /// [`NeutronDenom::check_admin`] is enabled.
+    fn assert_admin<Q: CustomQuery + DeserializeOwned>(
+        &self,
+        deps: Deps<Q>,
+        env: &Env,
+    ) -> CwTokenResult<()> {
+        if !self.check_admin {
+            return Ok(());
+        }
+        let admin = self.query_admin(deps)?;
+        if admin != env.contract.address.as_str() {
+            return Err(CwTokenError::NotDenomAdmin {
+                denom: self.to_string(),
+                address: env.contract.address.to_string(),
+                admin: (!admin.is_empty()).then_some(admin),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns a [`NeutronMsg::MintTokens`] message minting `amount` tokens
+    /// to `recipient`.
+    fn mint_msg<C: DeserializeOwned + Serialize>(
+        &self,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg<C>> {
+        Ok(CosmosMsg::Custom(into_custom(&NeutronMsg::MintTokens {
+            denom: self.to_string(),
+            amount,
+            mint_to_address: recipient.to_string(),
+        })?))
+    }
+}
+
+impl Display for NeutronDenom {
+    /// Returns the full denom of the token, in the format
+    /// `factory/{owner}/{subdenom}`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "factory/{}/{}", self.owner, self.subdenom)
+    }
+}
+
+impl<Q: CustomQuery> Query<Q> for NeutronDenom {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
+        Ok(deps
+            .querier
+            .query_balance(address, self.to_string())?
+            .amount)
+    }
+
+    /// Queries the total supply using the token's [`SupplyBackend`].
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        self.supply_backend
+            .query_total_supply(deps, self.to_string())
+    }
+
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        Ok(InflationProtection::load(deps.storage, &self.to_string())?)
+    }
+}
+
+impl<C, Q> Mint<C, Q> for NeutronDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery + DeserializeOwned,
+{
+    /// Mint tokens to the `recipient`. If [`InflationProtection::DeadShares`]
+    /// is configured, the dead shares are minted to the contract out of
+    /// `amount` on the first mint.
+    fn mint(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.assert_admin(deps.as_ref(), env)?;
+
+        let mut res = Response::new();
+        let mut event = Event::new("apollo/cw-vault-token/neutron").add_attributes(vec![
+            attr("action", "mint"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        // lock dead shares in the contract on the first mint, if configured
+        let dead_shares = take_dead_shares(deps.storage, &self.to_string(), amount)?;
+        self.supply_backend
+            .record_mint(deps.storage, &self.to_string(), amount)?;
+        if !dead_shares.is_zero() {
+            res = res.add_message(self.mint_msg(&env.contract.address, dead_shares)?);
+            event = event.add_attribute("dead_shares", dead_shares.to_string());
+        }
+
+        Ok(res
+            .add_message(self.mint_msg(recipient, amount - dead_shares)?)
+            .add_event(event))
+    }
+}
+
+impl<C, Q> Burn<C, Q> for NeutronDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery + DeserializeOwned,
+{
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.assert_admin(deps.as_ref(), env)?;
+        self.supply_backend
+            .record_burn(deps.storage, &self.to_string(), amount)?;
+
+        let burn_msg: C = into_custom(&NeutronMsg::BurnTokens {
+            denom: self.to_string(),
+            amount,
+            burn_from_address: String::new(),
+        })?;
+
+        let event = Event::new("apollo/cw-vault-token/neutron").add_attributes(vec![
+            attr("action", "burn"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_message(CosmosMsg::Custom(burn_msg))
+            .add_event(event))
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for NeutronDenom {
+    fn transfer(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let send_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: self.to_string(),
+                amount,
+            }],
+        };
+
+        let event = Event::new("apollo/cw-vault-token/neutron").add_attributes(vec![
+            attr("action", "transfer"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(send_msg).add_event(event))
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+/// Optional instantiate message for a [`NeutronDenom`].
+pub struct NeutronDenomInstantiateMsg {
+    /// Optional protection against the inflation attack. Defaults to
+    /// [`InflationProtection::None`].
+    pub inflation_protection: Option<InflationProtection>,
+}
+
+impl<C, Q> Instantiate<C, Q> for NeutronDenom
+where
+    C: CustomMsg + DeserializeOwned,
+    Q: CustomQuery,
+{
+    type InitInfo = NeutronDenomInstantiateMsg;
+
+    /// Creates the denom. Note that the denom is always created by the
+    /// contract, so [`NeutronDenom::owner`] must be the contract's address.
+    fn instantiate(&self, deps: DepsMut<Q>, msg: NeutronDenomInstantiateMsg) -> CwTokenResponse<C> {
+        if let Some(inflation_protection) = &msg.inflation_protection {
+            inflation_protection.save(deps.storage, &self.to_string())?;
+        }
+
+        let init_msg: C = into_custom(&NeutronMsg::CreateDenom {
+            subdenom: self.subdenom.clone(),
+        })?;
+
+        let init_event =
+            Event::new("apollo/cw-token/instantiate").add_attribute("denom", self.to_string());
+        Ok(Response::new()
+            .add_message(CosmosMsg::Custom(init_msg))
+            .add_event(init_event))
+    }
+
+    /// Instantiate from binary encoded [`NeutronDenomInstantiateMsg`]. Since
+    /// all fields are optional, `init_info` may be `None`.
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        let msg = init_info
+            .map(|init_info| from_binary(&init_info))
+            .transpose()?
+            .unwrap_or_default();
+        self.instantiate(deps, msg)
+    }
+}
+
+impl<Q: CustomQuery> Receive<Q> for NeutronDenom {
+    /// Validates that `amount` tokens were sent in `info.funds`, according to
+    /// the [`ReceiveMode`] of the token, and returns the leftover funds.
+    fn receive(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        info: &MessageInfo,
+        amount: Uint128,
+    ) -> CwTokenResult<Vec<Coin>> {
+        self.receive_mode
+            .receive(self.to_string(), &info.funds, amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::marker::PhantomData;
+
+    use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{to_binary, ContractResult, OwnedDeps, SystemError, SystemResult};
+
+    use super::*;
+
+    const SUBDENOM: &str = "subdenom";
+
+    /// Mock dependencies answering the token factory custom queries for
+    /// denoms created by `admin`.
+    fn mock_neutron_dependencies(
+        admin: &str,
+        balances: &[(&str, &[Coin])],
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier<NeutronQuery>, NeutronQuery> {
+        let admin = admin.to_string();
+        let querier = MockQuerier::<NeutronQuery>::new(balances).with_custom_handler(
+            move |query| match query {
+                NeutronQuery::DenomAdmin { subdenom } => match subdenom.starts_with("factory/") {
+                    true => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&DenomAdminResponse {
+                            admin: admin.clone(),
+                        })
+                        .unwrap(),
+                    )),
+                    false => SystemResult::Err(SystemError::InvalidRequest {
+                        error: format!("denom {subdenom} not found"),
+                        request: Binary::default(),
+                    }),
+                },
+            },
+        );
+
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        }
+    }
+
+    #[test]
+    fn to_string() {
+        let denom = NeutronDenom::new("owner".to_string(), SUBDENOM.to_string());
+        assert_eq!(denom.to_string(), "factory/owner/subdenom");
+        assert_eq!(
+            NeutronDenom::from_native_denom("factory/owner/subdenom").unwrap(),
+            denom
+        );
+        assert!(NeutronDenom::from_native_denom("factory/owner").is_err());
+    }
+
+    #[test]
+    fn custom_queries() {
+        let env = mock_env();
+        let deps = mock_neutron_dependencies(env.contract.address.as_str(), &[]);
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        assert_eq!(
+            denom.query_admin(deps.as_ref()).unwrap(),
+            env.contract.address.to_string()
+        );
+    }
+
+    #[test]
+    fn admin_check() {
+        let env = mock_env();
+        let mut deps = mock_neutron_dependencies("other_contract", &[]);
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        // Without the check, the messages are returned and fail on chain
+        let _: Response<NeutronMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+
+        let denom = denom.with_admin_check(true);
+        let expected = CwTokenError::NotDenomAdmin {
+            denom: denom.to_string(),
+            address: env.contract.address.to_string(),
+            admin: Some("other_contract".to_string()),
+        };
+        let err = Mint::<NeutronMsg, _>::mint(
+            &denom,
+            deps.as_mut(),
+            &env,
+            &recipient,
+            Uint128::from(1000u128),
+        )
+        .unwrap_err();
+        assert_eq!(err, expected);
+        let err = Burn::<NeutronMsg, _>::burn(&denom, deps.as_mut(), &env, Uint128::from(1u128))
+            .unwrap_err();
+        assert_eq!(err, expected);
+
+        // The admin passes the check
+        let mut deps = mock_neutron_dependencies(env.contract.address.as_str(), &[]);
+        let _: Response<NeutronMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+    }
+
+    #[test]
+    fn instantiate_and_mint_dead_shares() {
+        let env = mock_env();
+        let mut deps = mock_neutron_dependencies(env.contract.address.as_str(), &[]);
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let init_info = NeutronDenomInstantiateMsg {
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(1000u128),
+            }),
+        };
+        let res: Response<NeutronMsg> = denom.instantiate(deps.as_mut(), init_info).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(NeutronMsg::CreateDenom {
+                subdenom: SUBDENOM.to_string(),
+            })
+        );
+
+        // Dead shares are minted to the contract on the first mint
+        let res: Response<NeutronMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Custom(NeutronMsg::MintTokens {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(1000u128),
+                    mint_to_address: env.contract.address.to_string(),
+                }),
+                CosmosMsg::Custom(NeutronMsg::MintTokens {
+                    denom: denom.to_string(),
+                    amount: Uint128::from(4000u128),
+                    mint_to_address: recipient.to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn burn_and_transfer() {
+        let env = mock_env();
+        let mut deps = mock_neutron_dependencies(env.contract.address.as_str(), &[]);
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+        let amount = Uint128::from(1000u128);
+
+        let res: Response<NeutronMsg> = denom.burn(deps.as_mut(), &env, amount).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(NeutronMsg::BurnTokens {
+                denom: denom.to_string(),
+                amount,
+                burn_from_address: String::new(),
+            })
+        );
+
+        let res: Response<NeutronMsg> = denom
+            .transfer(deps.as_mut(), &env, &recipient, amount)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: denom.to_string(),
+                    amount,
+                }],
+            })
+        );
+
+        let err =
+            Burn::<NeutronMsg, _>::burn(&denom, deps.as_mut(), &env, Uint128::zero()).unwrap_err();
+        assert_eq!(err, CwTokenError::ZeroAmount {});
+    }
+
+    #[test]
+    fn query_balance_and_receive() {
+        let env = mock_env();
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let funds = [Coin::new(1000, denom.to_string())];
+        let mut deps =
+            mock_neutron_dependencies(env.contract.address.as_str(), &[("user", &funds)]);
+
+        assert_eq!(
+            denom.query_balance(deps.as_ref(), "user").unwrap(),
+            Uint128::from(1000u128)
+        );
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("user"),
+            funds: funds.to_vec(),
+        };
+        let leftover = denom
+            .receive(deps.as_mut(), &env, &info, Uint128::from(1000u128))
+            .unwrap();
+        assert!(leftover.is_empty());
+        denom
+            .receive(deps.as_mut(), &env, &info, Uint128::from(1001u128))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn serializes_like_neutron_bindings() {
+        let msg = NeutronMsg::MintTokens {
+            denom: "factory/owner/subdenom".to_string(),
+            amount: Uint128::from(1000u128),
+            mint_to_address: "recipient".to_string(),
+        };
+        assert_eq!(
+            to_binary(&msg).unwrap(),
+            Binary::from(
+                br#"{"mint_tokens":{"denom":"factory/owner/subdenom","amount":"1000","mint_to_address":"recipient"}}"#
+                    .as_slice()
+            )
+        );
+    }
+
+    /// Stand-in for `neutron_sdk::bindings::msg::NeutronMsg`, which has more
+    /// variants than [`NeutronMsg`] and can't implement `From<NeutronMsg>`
+    /// in a downstream contract due to the orphan rule.
+    #[cw_serde]
+    enum SdkNeutronMsg {
+        SubmitTx {
+            connection_id: String,
+        },
+        CreateDenom {
+            subdenom: String,
+        },
+        MintTokens {
+            denom: String,
+            amount: Uint128,
+            mint_to_address: String,
+        },
+        BurnTokens {
+            denom: String,
+            amount: Uint128,
+            #[serde(default)]
+            burn_from_address: String,
+        },
+    }
+
+    impl CustomMsg for SdkNeutronMsg {}
+
+    /// Stand-in for `neutron_sdk::bindings::query::NeutronQuery`.
+    #[cw_serde]
+    enum SdkNeutronQuery {
+        InterchainAccountAddress { owner_address: String },
+        DenomAdmin { subdenom: String },
+    }
+
+    impl CustomQuery for SdkNeutronQuery {}
+
+    #[test]
+    fn converts_into_sdk_types() {
+        let env = mock_env();
+        let admin = env.contract.address.to_string();
+        let querier =
+            MockQuerier::<SdkNeutronQuery>::new(&[]).with_custom_handler(
+                move |query| match query {
+                    SdkNeutronQuery::DenomAdmin { .. } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&DenomAdminResponse {
+                            admin: admin.clone(),
+                        })
+                        .unwrap(),
+                    )),
+                    _ => panic!("unexpected query {query:?}"),
+                },
+            );
+        let mut deps: OwnedDeps<_, _, _, SdkNeutronQuery> = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        };
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string())
+            .with_admin_check(true);
+        let recipient = Addr::unchecked("recipient");
+
+        let res: Response<SdkNeutronMsg> = denom
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkNeutronMsg::CreateDenom {
+                subdenom: SUBDENOM.to_string(),
+            })
+        );
+        let res: Response<SdkNeutronMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkNeutronMsg::MintTokens {
+                denom: denom.to_string(),
+                amount: Uint128::from(1000u128),
+                mint_to_address: recipient.to_string(),
+            })
+        );
+        let res: Response<SdkNeutronMsg> = denom
+            .burn(deps.as_mut(), &env, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(SdkNeutronMsg::BurnTokens {
+                denom: denom.to_string(),
+                amount: Uint128::from(1000u128),
+                burn_from_address: String::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_types_that_cant_represent_neutron_msgs() {
+        let env = mock_env();
+        let mut deps = mock_neutron_dependencies(env.contract.address.as_str(), &[]);
+        let denom = NeutronDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+
+        // `Empty` deserializes from any JSON object, but can't serialize back
+        let err = Mint::<cosmwasm_std::Empty, _>::mint(
+            &denom,
+            deps.as_mut(),
+            &env,
+            &Addr::unchecked("recipient"),
+            Uint128::from(1000u128),
+        )
+        .unwrap_err();
+        assert!(matches!(err, CwTokenError::Std(StdError::ParseErr { .. })));
+        assert!(into_custom::<_, SdkNeutronMsg>(&NeutronMsg::CreateDenom {
+            subdenom: SUBDENOM.to_string()
+        })
+        .is_ok());
+    }
+}
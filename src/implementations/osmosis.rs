@@ -1,15 +1,22 @@
-use crate::{Burn, CwTokenResponse, CwTokenResult, Instantiate, Mint, Receive, VaultToken};
+use crate::inflation::take_dead_shares;
+use crate::{
+    Burn, BurnFrom, CwTokenError, CwTokenResponse, CwTokenResult, InflationProtection, Instantiate,
+    Mint, Query, Receive, SupplyBackend, Transfer,
+};
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    attr, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo, Response,
-    StdError, StdResult, Uint128,
+    attr, from_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, CustomQuery, Deps,
+    DepsMut, Env, Event, MessageInfo, Reply, Response, StdError, SubMsg, Uint128,
 };
-use osmosis_std::types::cosmos::bank::v1beta1::BankQuerier;
+use cw_utils::ParseReplyError;
+use osmosis_std::types::cosmos::bank::v1beta1::{BankQuerier, Metadata};
 use osmosis_std::types::cosmos::base::v1beta1::Coin as CoinMsg;
-use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgCreateDenom, MsgMint};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
+    DenomAuthorityMetadata, MsgBurn, MsgChangeAdmin, MsgCreateDenom, MsgCreateDenomResponse,
+    MsgMint, MsgSetBeforeSendHook, MsgSetDenomMetadata, TokenfactoryQuerier,
+};
 use std::fmt::Display;
-use std::str::FromStr;
 
 #[cw_serde]
 /// Representation of a native token created using the Osmosis Token Factory.
@@ -20,7 +27,8 @@ use std::str::FromStr;
 /// using [`OsmosisDenom::new`] or [`OsmosisDenom::from_native_denom`]. Note
 /// that currently only the creator of the denom can mint or burn it.
 ///
-/// This struct implements the [`VaultToken`] trait.
+/// This struct implements the [`crate::VaultToken`] trait for any custom
+/// message and query type.
 pub struct OsmosisDenom {
     /// Creator and owner of the denom. Only this address can mint and burn
     /// tokens.
@@ -28,30 +36,340 @@ pub struct OsmosisDenom {
     /// The subdenom of the token. All tokens created using the token factory
     /// have the format `factory/{owner}/{subdenom}`.
     pub subdenom: String,
+    /// How [`Receive::receive`] validates `info.funds`. Defaults to
+    /// [`ReceiveMode::Lenient`].
+    #[serde(default)]
+    pub receive_mode: ReceiveMode,
+    /// How [`Query::query_total_supply`] queries the total supply. Defaults
+    /// to [`SupplyBackend::Stargate`].
+    #[serde(default)]
+    pub supply_backend: SupplyBackend,
+    /// Whether [`Mint::mint`], [`Burn::burn`] and [`BurnFrom::burn_from`]
+    /// first query the token factory to check that the contract is the admin
+    /// of the denom, returning [`CwTokenError::NotDenomAdmin`] instead of a
+    /// failed transaction if it is not. Requires the token factory
+    /// `DenomAuthorityMetadata` stargate query. Defaults to `false`.
+    #[serde(default)]
+    pub check_admin: bool,
+}
+
+#[cw_serde]
+#[derive(Default, Copy)]
+/// How [`OsmosisDenom::receive`] validates the funds sent with a message.
+pub enum ReceiveMode {
+    /// Only the vault token may be sent, and the sum of all coins of its
+    /// denom must be exactly the amount to receive. No funds are left over.
+    Strict,
+    /// Coins of the vault token's denom are summed, and must be exactly the
+    /// amount to receive. Any other denoms are returned as leftover funds.
+    /// This matches the behaviour of earlier versions, which accepted extra
+    /// coins of other denoms.
+    ///
+    /// A surplus of the vault token itself is intentionally rejected with
+    /// [`CwTokenError::UnexpectedFunds`] rather than returned as leftover, as
+    /// in earlier versions. Callers that receive a variable amount must pass
+    /// the amount actually sent.
+    #[default]
+    Lenient,
+}
+
+impl ReceiveMode {
+    /// Validates that `amount` tokens of `denom` were sent in `funds` and
+    /// returns the leftover funds. Shared by the native token
+    /// implementations.
+    pub(crate) fn receive(
+        self,
+        denom: String,
+        funds: &[Coin],
+        amount: Uint128,
+    ) -> CwTokenResult<Vec<Coin>> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let required = Coin { denom, amount };
+        let received = Coin {
+            denom: required.denom.clone(),
+            amount: funds
+                .iter()
+                .filter(|c| c.denom == required.denom)
+                .map(|c| c.amount)
+                .sum(),
+        };
+
+        if received.amount < required.amount {
+            return Err(CwTokenError::InsufficientFunds {
+                expected: required,
+                received,
+            });
+        }
+
+        let leftover: Vec<Coin> = funds
+            .iter()
+            .filter(|c| c.denom != required.denom)
+            .cloned()
+            .collect();
+        if received.amount != required.amount
+            || (self == ReceiveMode::Strict && !leftover.is_empty())
+        {
+            return Err(CwTokenError::UnexpectedFunds {
+                expected: required,
+                received: funds.to_vec(),
+            });
+        }
+        Ok(leftover)
+    }
 }
 
 impl OsmosisDenom {
     /// Creates a new [`OsmosisDenom`] obj instance
     pub const fn new(owner: String, subdenom: String) -> Self {
-        Self { owner, subdenom }
+        Self {
+            owner,
+            subdenom,
+            receive_mode: ReceiveMode::Lenient,
+            supply_backend: SupplyBackend::Stargate,
+            check_admin: false,
+        }
+    }
+
+    /// Sets the [`ReceiveMode`] used by [`Receive::receive`].
+    pub const fn with_receive_mode(mut self, receive_mode: ReceiveMode) -> Self {
+        self.receive_mode = receive_mode;
+        self
+    }
+
+    /// Sets the [`SupplyBackend`] used by [`Query::query_total_supply`].
+    pub const fn with_supply_backend(mut self, supply_backend: SupplyBackend) -> Self {
+        self.supply_backend = supply_backend;
+        self
+    }
+
+    /// Sets whether mints and burns check that the contract is the admin of
+    /// the denom. See [`OsmosisDenom::check_admin`].
+    pub const fn with_admin_check(mut self, check_admin: bool) -> Self {
+        self.check_admin = check_admin;
+        self
     }
 
     /// Create an [`OsmosisDenom`] from a string. `denom` must be the full denom
     /// of the token, in the format `factory/{owner}/{subdenom}`.
     ///
     /// ## Errors
-    /// Will return [`StdError`] if `denom` does not follow the required format.
-    pub fn from_native_denom(denom: &str) -> StdResult<Self> {
-        let parts: Vec<_> = denom.split('/').collect();
+    /// Will return [`CwTokenError::InvalidDenom`] if `denom` does not follow
+    /// the required format.
+    pub fn from_native_denom(denom: &str) -> CwTokenResult<Self> {
+        let (owner, subdenom) = parse_factory_denom(denom)?;
+        Ok(Self::new(owner, subdenom))
+    }
 
-        if parts.len() != 3 || parts[0] != "factory" {
-            return Err(StdError::generic_err(
-                "Can't create OsmosisDenom from invalid denom.",
-            ));
+    /// Returns a `MsgMint` message minting `amount` tokens to `recipient`.
+    fn mint_msg<C>(&self, env: &Env, recipient: &Addr, amount: Uint128) -> CosmosMsg<C> {
+        (MsgMint {
+            amount: Some(CoinMsg {
+                denom: self.to_string(),
+                amount: amount.to_string(),
+            }),
+            sender: env.contract.address.to_string(),
+            mint_to_address: recipient.to_string(),
+        })
+        .into()
+    }
+
+    /// Returns a `MsgSetDenomMetadata` message sent by `sender`, which must be
+    /// the admin of the denom. The `base` of `metadata` is always set to the
+    /// denom of the token. Like `MsgCreateDenom`, the instantiate message is
+    /// sent by [`OsmosisDenom::owner`], the creator and initial admin, while
+    /// [`OsmosisDenom::set_metadata`] is sent by the contract, like the other
+    /// admin messages, since the admin may have changed since.
+    fn set_metadata_msg<C>(&self, sender: String, mut metadata: Metadata) -> CosmosMsg<C> {
+        metadata.base = self.to_string();
+        MsgSetDenomMetadata {
+            sender,
+            metadata: Some(metadata),
         }
+        .into()
+    }
+
+    /// ## Description
+    /// Updates the bank metadata of the token, e.g. its display denom and
+    /// decimals, using `MsgSetDenomMetadata`. The `base` of `metadata` is
+    /// always set to the denom of the token. Only the admin of the denom can
+    /// set its metadata, so this must be called by the admin contract.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::NotDenomAdmin`] if
+    /// [`OsmosisDenom::check_admin`] is enabled and the contract is not the
+    /// admin of the denom. The transaction will fail if the metadata is
+    /// invalid.
+    pub fn set_metadata<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        env: &Env,
+        metadata: Metadata,
+    ) -> CwTokenResponse<C> {
+        self.assert_admin(deps, env)?;
 
-        Ok(Self::new(parts[1].to_string(), parts[2].to_string()))
+        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+            attr("action", "set_metadata"),
+            attr("denom", self.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_message(self.set_metadata_msg(env.contract.address.to_string(), metadata))
+            .add_event(event))
     }
+
+    /// ## Description
+    /// Transfers the admin rights of the denom to `new_admin` using
+    /// `MsgChangeAdmin`, e.g. to hand the denom over to a new vault contract
+    /// during a migration. Only the current admin can change the admin, so
+    /// this must be called by the admin contract. Note that
+    /// [`OsmosisDenom::owner`] is part of the denom and therefore does not
+    /// change.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::NotDenomAdmin`] if
+    /// [`OsmosisDenom::check_admin`] is enabled and the contract is not the
+    /// admin of the denom.
+    pub fn change_admin<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        env: &Env,
+        new_admin: &Addr,
+    ) -> CwTokenResponse<C> {
+        self.assert_admin(deps, env)?;
+
+        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+            attr("action", "change_admin"),
+            attr("denom", self.to_string()),
+            attr("new_admin", new_admin.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_message(MsgChangeAdmin {
+                sender: env.contract.address.to_string(),
+                denom: self.to_string(),
+                new_admin: new_admin.to_string(),
+            })
+            .add_event(event))
+    }
+
+    /// ## Description
+    /// Queries the authority metadata of the denom, i.e. its current admin,
+    /// using the token factory `DenomAuthorityMetadata` stargate query.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the query fails, e.g. because the denom does
+    /// not exist.
+    pub fn query_authority_metadata<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+    ) -> CwTokenResult<DenomAuthorityMetadata> {
+        Ok(TokenfactoryQuerier::new(&deps.querier)
+            .denom_authority_metadata(self.to_string())?
+            .authority_metadata
+            .ok_or_else(|| StdError::not_found("authority metadata in response"))?)
+    }
+
+    /// ## Description
+    /// Sets `cosmwasm_address` as the before send hook of the denom using
+    /// `MsgSetBeforeSendHook`. The token factory module then calls the
+    /// `sudo` entry point of the contract before every transfer of the token,
+    /// with a [`crate::before_send::BeforeSendSudoMsg`]. This allows the vault
+    /// to keep per-holder state, or to restrict transfers. Passing `None`
+    /// removes the hook. Only the admin of the denom can set the hook.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::NotDenomAdmin`] if
+    /// [`OsmosisDenom::check_admin`] is enabled and the contract is not the
+    /// admin of the denom.
+    pub fn set_before_send_hook<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        env: &Env,
+        cosmwasm_address: Option<&Addr>,
+    ) -> CwTokenResponse<C> {
+        self.assert_admin(deps, env)?;
+
+        let cosmwasm_address = cosmwasm_address.map(Addr::to_string).unwrap_or_default();
+        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+            attr("action", "set_before_send_hook"),
+            attr("denom", self.to_string()),
+            attr("cosmwasm_address", &cosmwasm_address),
+        ]);
+        Ok(Response::new()
+            .add_message(MsgSetBeforeSendHook {
+                sender: env.contract.address.to_string(),
+                denom: self.to_string(),
+                cosmwasm_address,
+            })
+            .add_event(event))
+    }
+
+    /// ## Description
+    /// Queries the before send hook contract of the denom using the token
+    /// factory `BeforeSendHookAddress` stargate query. Returns `None` if no
+    /// hook is set.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the query fails.
+    pub fn query_before_send_hook<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+    ) -> CwTokenResult<Option<Addr>> {
+        let address = TokenfactoryQuerier::new(&deps.querier)
+            .before_send_hook_address(self.to_string())?
+            .cosmwasm_address;
+        Ok((!address.is_empty()).then(|| Addr::unchecked(address)))
+    }
+
+    /// Checks that the contract is the admin of the denom, if
+    /// [`OsmosisDenom::check_admin`] is enabled.
+    fn assert_admin<Q: CustomQuery>(&self, deps: Deps<Q>, env: &Env) -> CwTokenResult<()> {
+        if !self.check_admin {
+            return Ok(());
+        }
+        let admin = self.query_authority_metadata(deps)?.admin;
+        if admin != env.contract.address.as_str() {
+            return Err(CwTokenError::NotDenomAdmin {
+                denom: self.to_string(),
+                address: env.contract.address.to_string(),
+                admin: (!admin.is_empty()).then_some(admin),
+            });
+        }
+        Ok(())
+    }
+
+    /// ## Description
+    /// Queries the bank metadata of the token using the stargate query
+    /// `/cosmos.bank.v1beta1.Query/DenomMetadata`.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the query fails, e.g. because no metadata has
+    /// been set for the denom.
+    pub fn query_metadata<Q: CustomQuery>(&self, deps: Deps<Q>) -> CwTokenResult<Metadata> {
+        Ok(BankQuerier::new(&deps.querier)
+            .denom_metadata(self.to_string())?
+            .metadata
+            .ok_or_else(|| StdError::not_found("metadata in denom metadata response"))?)
+    }
+}
+
+/// Splits a token factory denom in the format `factory/{owner}/{subdenom}`
+/// into its owner and subdenom.
+///
+/// ## Errors
+/// Returns [`CwTokenError::InvalidDenom`] if `denom` does not follow the
+/// format.
+pub(crate) fn parse_factory_denom(denom: &str) -> CwTokenResult<(String, String)> {
+    let parts: Vec<_> = denom.split('/').collect();
+
+    if parts.len() != 3 || parts[0] != "factory" {
+        return Err(CwTokenError::InvalidDenom {
+            denom: denom.to_string(),
+        });
+    }
+
+    Ok((parts[1].to_string(), parts[2].to_string()))
 }
 
 impl Display for OsmosisDenom {
@@ -62,56 +380,73 @@ impl Display for OsmosisDenom {
     }
 }
 
-impl VaultToken for OsmosisDenom {
-    fn query_balance<A: Into<String>>(&self, deps: Deps, address: A) -> CwTokenResult<Uint128> {
+impl<Q: CustomQuery> Query<Q> for OsmosisDenom {
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128> {
         Ok(deps
             .querier
             .query_balance(address, self.to_string())?
             .amount)
     }
 
-    fn query_total_supply(&self, deps: Deps) -> CwTokenResult<Uint128> {
-        let bank_querier = BankQuerier::new(&deps.querier);
-        let amount_str = bank_querier
-            .supply_of(self.to_string())?
-            .amount
-            .map(|c| c.amount)
-            .ok_or_else(|| StdError::not_found("amount in supply response"))?;
-        Ok(Uint128::from_str(&amount_str)?)
+    /// Queries the total supply using the token's [`SupplyBackend`].
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        self.supply_backend
+            .query_total_supply(deps, self.to_string())
+    }
+
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection> {
+        Ok(InflationProtection::load(deps.storage, &self.to_string())?)
     }
 }
 
-impl Mint for OsmosisDenom {
+impl<C: CustomMsg, Q: CustomQuery> Mint<C, Q> for OsmosisDenom {
+    /// Mint tokens to the `recipient`. If [`InflationProtection::DeadShares`]
+    /// is configured, the dead shares are minted to the contract out of
+    /// `amount` on the first mint.
     fn mint(
         &self,
-        _deps: DepsMut,
+        deps: DepsMut<Q>,
         env: &Env,
         recipient: &Addr,
         amount: Uint128,
-    ) -> CwTokenResponse {
-        let mint_msg: CosmosMsg = (MsgMint {
-            amount: Some(CoinMsg {
-                denom: self.to_string(),
-                amount: amount.to_string(),
-            }),
-            sender: env.contract.address.to_string(),
-            mint_to_address: recipient.to_string(),
-        })
-        .into();
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.assert_admin(deps.as_ref(), env)?;
 
-        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+        let mut res = Response::new();
+        let mut event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
             attr("action", "mint"),
             attr("denom", self.to_string()),
             attr("amount", amount.to_string()),
             attr("recipient", recipient.to_string()),
         ]);
 
-        Ok(Response::new().add_message(mint_msg).add_event(event))
+        // lock dead shares in the contract on the first mint, if configured
+        let dead_shares = take_dead_shares(deps.storage, &self.to_string(), amount)?;
+        self.supply_backend
+            .record_mint(deps.storage, &self.to_string(), amount)?;
+        if !dead_shares.is_zero() {
+            res = res.add_message(self.mint_msg(env, &env.contract.address, dead_shares));
+            event = event.add_attribute("dead_shares", dead_shares.to_string());
+        }
+
+        Ok(res
+            .add_message(self.mint_msg(env, recipient, amount - dead_shares))
+            .add_event(event))
     }
 }
 
-impl Burn for OsmosisDenom {
-    fn burn(&self, _deps: DepsMut, env: &Env, amount: Uint128) -> CwTokenResponse {
+impl<C: CustomMsg, Q: CustomQuery> Burn<C, Q> for OsmosisDenom {
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.assert_admin(deps.as_ref(), env)?;
+        self.supply_backend
+            .record_burn(deps.storage, &self.to_string(), amount)?;
+
         let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
             attr("action", "burn"),
             attr("denom", self.to_string()),
@@ -130,45 +465,226 @@ impl Burn for OsmosisDenom {
     }
 }
 
-impl Instantiate for OsmosisDenom {
-    fn instantiate(&self, _deps: DepsMut, _init_info: Option<Binary>) -> CwTokenResponse {
-        let init_msg: CosmosMsg = (MsgCreateDenom {
+impl<C: CustomMsg, Q: CustomQuery> BurnFrom<C, Q> for OsmosisDenom {
+    /// Burns tokens directly from the `owner`'s balance using `MsgBurn` with
+    /// `burn_from_address` set. Note that this requires the chain to allow
+    /// burning from addresses other than the sender, which is not enabled on
+    /// all versions of the Osmosis TokenFactory module. If it is not enabled,
+    /// the transaction will fail.
+    fn burn_from(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        owner: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+        self.assert_admin(deps.as_ref(), env)?;
+        self.supply_backend
+            .record_burn(deps.storage, &self.to_string(), amount)?;
+
+        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+            attr("action", "burn_from"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("owner", owner.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_message(MsgBurn {
+                amount: Some(CoinMsg {
+                    denom: self.to_string(),
+                    amount: amount.to_string(),
+                }),
+                sender: env.contract.address.to_string(),
+                burn_from_address: owner.to_string(),
+            })
+            .add_event(event))
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Transfer<C, Q> for OsmosisDenom {
+    fn transfer(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C> {
+        if amount.is_zero() {
+            return Err(CwTokenError::ZeroAmount {});
+        }
+
+        let send_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: self.to_string(),
+                amount,
+            }],
+        };
+
+        let event = Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+            attr("action", "transfer"),
+            attr("denom", self.to_string()),
+            attr("amount", amount.to_string()),
+            attr("recipient", recipient.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(send_msg).add_event(event))
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+/// Optional instantiate message for an [`OsmosisDenom`].
+pub struct OsmosisDenomInstantiateMsg {
+    /// Optional protection against the inflation attack. Defaults to
+    /// [`InflationProtection::None`].
+    pub inflation_protection: Option<InflationProtection>,
+    /// Optional bank metadata of the token, so that wallets can show its name,
+    /// symbol and decimals. The `base` is always set to the denom of the
+    /// token. Can be updated later with [`OsmosisDenom::set_metadata`].
+    #[serde(default)]
+    pub metadata: Option<Metadata>,
+}
+
+impl<C: CustomMsg, Q: CustomQuery> Instantiate<C, Q> for OsmosisDenom {
+    type InitInfo = OsmosisDenomInstantiateMsg;
+
+    fn instantiate(&self, deps: DepsMut<Q>, msg: OsmosisDenomInstantiateMsg) -> CwTokenResponse<C> {
+        self.instantiate_response(deps, msg, None)
+    }
+
+    /// Instantiate from binary encoded [`OsmosisDenomInstantiateMsg`]. Since
+    /// all fields are optional, `init_info` may be `None`.
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        let msg = init_info
+            .map(|init_info| from_binary(&init_info))
+            .transpose()?
+            .unwrap_or_default();
+        self.instantiate(deps, msg)
+    }
+}
+
+impl OsmosisDenom {
+    /// ## Description
+    /// Like [`Instantiate::instantiate`], but sends `MsgCreateDenom` as a
+    /// [`SubMsg`] replying on success with `reply_id`. The reply should be
+    /// passed to [`OsmosisDenom::handle_reply`] in the `reply` entry point of
+    /// the contract, to confirm the created denom.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if `msg` contains an invalid
+    /// [`InflationProtection`].
+    pub fn instantiate_with_reply<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        msg: OsmosisDenomInstantiateMsg,
+        reply_id: u64,
+    ) -> CwTokenResponse<C> {
+        self.instantiate_response(deps, msg, Some(reply_id))
+    }
+
+    /// ## Description
+    /// Handles the reply to the `MsgCreateDenom` sent by
+    /// [`OsmosisDenom::instantiate_with_reply`]. Decodes the
+    /// `MsgCreateDenomResponse` and checks that the created denom matches
+    /// this token. Returns the confirmed denom, so that the contract can
+    /// persist it.
+    ///
+    /// ## Errors
+    /// - [`CwTokenError::InvalidReplyId`] if the id of `reply` is not
+    ///   `reply_id`.
+    /// - [`CwTokenError::ParseReplyError`] if the sub-message failed or its
+    ///   response can't be decoded.
+    /// - [`CwTokenError::DenomMismatch`] if the created denom is not the
+    ///   denom of this token.
+    pub fn handle_reply(&self, reply: Reply, reply_id: u64) -> CwTokenResult<String> {
+        if reply.id != reply_id {
+            return Err(CwTokenError::InvalidReplyId {});
+        }
+
+        let data = reply
+            .result
+            .into_result()
+            .map_err(ParseReplyError::SubMsgFailure)?
+            .data
+            .ok_or_else(|| ParseReplyError::ParseFailure("Missing reply data".to_string()))?;
+        let new_token_denom = MsgCreateDenomResponse::try_from(data)
+            .map_err(|e| ParseReplyError::ParseFailure(e.to_string()))?
+            .new_token_denom;
+
+        if new_token_denom != self.to_string() {
+            return Err(CwTokenError::DenomMismatch {
+                expected: self.to_string(),
+                actual: new_token_denom,
+            });
+        }
+        Ok(new_token_denom)
+    }
+
+    /// Returns the response creating the denom, with `MsgCreateDenom` sent as
+    /// a [`SubMsg`] replying on success if `reply_id` is set.
+    fn instantiate_response<C: CustomMsg, Q: CustomQuery>(
+        &self,
+        deps: DepsMut<Q>,
+        msg: OsmosisDenomInstantiateMsg,
+        reply_id: Option<u64>,
+    ) -> CwTokenResponse<C> {
+        if let Some(inflation_protection) = &msg.inflation_protection {
+            inflation_protection.save(deps.storage, &self.to_string())?;
+        }
+
+        let init_msg: CosmosMsg<C> = (MsgCreateDenom {
             sender: self.owner.clone(),
             subdenom: self.subdenom.clone(),
         })
         .into();
+        let init_msg = match reply_id {
+            Some(reply_id) => SubMsg::reply_on_success(init_msg, reply_id),
+            None => SubMsg::new(init_msg),
+        };
 
         let init_event =
             Event::new("apollo/cw-token/instantiate").add_attribute("denom", self.to_string());
-        Ok(Response::new().add_message(init_msg).add_event(init_event))
+        let mut res = Response::new()
+            .add_submessage(init_msg)
+            .add_event(init_event);
+
+        if let Some(metadata) = msg.metadata {
+            res = res.add_message(self.set_metadata_msg(self.owner.clone(), metadata));
+        }
+
+        Ok(res)
     }
 }
 
-impl Receive for OsmosisDenom {
+impl<Q: CustomQuery> Receive<Q> for OsmosisDenom {
+    /// Validates that `amount` tokens were sent in `info.funds`, according to
+    /// the [`ReceiveMode`] of the token, and returns the leftover funds.
     fn receive(
         &self,
-        _deps: DepsMut,
+        _deps: DepsMut<Q>,
         _env: &Env,
         info: &MessageInfo,
         amount: Uint128,
-    ) -> StdResult<()> {
-        let required = Coin {
-            denom: self.to_string(),
-            amount,
-        };
-        if !info.funds.contains(&required) {
-            return Err(StdError::generic_err(format!(
-                "Expected to receive {}",
-                required
-            )));
-        }
-        Ok(())
+    ) -> CwTokenResult<Vec<Coin>> {
+        self.receive_mode
+            .receive(self.to_string(), &info.funds, amount)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use std::marker::PhantomData;
+
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{Empty, OwnedDeps, SubMsgResponse, SubMsgResult};
 
     use test_case::test_case;
 
@@ -177,6 +693,20 @@ mod test {
     const SENDER: &str = "sender";
     const SUBDENOM: &str = "subdenom";
 
+    #[cw_serde]
+    enum TestMsg {
+        Noop {},
+    }
+
+    impl CustomMsg for TestMsg {}
+
+    #[cw_serde]
+    enum TestQuery {
+        Noop {},
+    }
+
+    impl CustomQuery for TestQuery {}
+
     #[test]
     fn to_string() {
         let denom = OsmosisDenom::new(SENDER.to_string(), SUBDENOM.to_string());
@@ -197,6 +727,299 @@ mod test {
         assert_eq!(denom.subdenom, "subdenom");
     }
 
+    #[test]
+    fn instantiate_binary_without_init_info() {
+        let mut deps = mock_dependencies();
+        let denom = OsmosisDenom::new(SENDER.to_string(), SUBDENOM.to_string());
+
+        let res: Response = denom.instantiate_binary(deps.as_mut(), None).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            MsgCreateDenom {
+                sender: SENDER.to_string(),
+                subdenom: SUBDENOM.to_string(),
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn instantiate_with_reply() {
+        let mut deps = mock_dependencies();
+        let denom = OsmosisDenom::new(SENDER.to_string(), SUBDENOM.to_string());
+
+        let res: Response = denom
+            .instantiate_with_reply(deps.as_mut(), Default::default(), 7)
+            .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_success(
+                MsgCreateDenom {
+                    sender: SENDER.to_string(),
+                    subdenom: SUBDENOM.to_string(),
+                },
+                7
+            )]
+        );
+    }
+
+    fn create_denom_reply(id: u64, result: Result<&str, &str>) -> Reply {
+        Reply {
+            id,
+            result: match result {
+                Ok(new_token_denom) => SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: new_token_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+                Err(err) => SubMsgResult::Err(err.to_string()),
+            },
+        }
+    }
+
+    #[test_case(create_denom_reply(7, Ok("factory/sender/subdenom")) => Ok("factory/sender/subdenom".to_string()) ; "confirmed denom")]
+    #[test_case(create_denom_reply(8, Ok("factory/sender/subdenom")) => Err(CwTokenError::InvalidReplyId {}) ; "wrong reply id")]
+    #[test_case(create_denom_reply(7, Ok("factory/sender/other")) => Err(CwTokenError::DenomMismatch { expected: "factory/sender/subdenom".to_string(), actual: "factory/sender/other".to_string() }) ; "denom mismatch")]
+    #[test_case(create_denom_reply(7, Err("out of gas")) => Err(CwTokenError::ParseReplyError(ParseReplyError::SubMsgFailure("out of gas".to_string()))) ; "sub-message failed")]
+    #[test_case(Reply { id: 7, result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }) } => matches Err(CwTokenError::ParseReplyError(ParseReplyError::ParseFailure(_))) ; "missing data")]
+    fn handle_reply(reply: Reply) -> CwTokenResult<String> {
+        OsmosisDenom::new(SENDER.to_string(), SUBDENOM.to_string()).handle_reply(reply, 7)
+    }
+
+    #[test]
+    fn mint_dead_shares() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let init_info = OsmosisDenomInstantiateMsg {
+            inflation_protection: Some(InflationProtection::DeadShares {
+                amount: Uint128::from(1000u128),
+            }),
+            metadata: None,
+        };
+        let _: Response = denom.instantiate(deps.as_mut(), init_info).unwrap();
+
+        // Dead shares are minted to the contract on the first mint
+        let res: Response = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                denom.mint_msg(&env, &env.contract.address, Uint128::from(1000u128)),
+                denom.mint_msg(&env, &recipient, Uint128::from(4000u128)),
+            ]
+        );
+
+        // Only on the first mint
+        let res: Response = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            denom.mint_msg(&env, &recipient, Uint128::from(5000u128))
+        );
+    }
+
+    #[test]
+    fn burn_from() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let owner = Addr::unchecked("owner");
+        let amount = Uint128::from(1000u128);
+
+        let res: Response = denom
+            .burn_from(deps.as_mut(), &env, &owner, amount)
+            .unwrap();
+
+        // Assert that a burn message from the owner's address is returned
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            MsgBurn {
+                amount: Some(CoinMsg {
+                    denom: denom.to_string(),
+                    amount: amount.to_string(),
+                }),
+                sender: env.contract.address.to_string(),
+                burn_from_address: owner.to_string(),
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn tracked_supply() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string())
+            .with_supply_backend(SupplyBackend::Tracked);
+        let recipient = Addr::unchecked("recipient");
+
+        let _: Response = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(5000u128))
+            .unwrap();
+        let _: Response =
+            Burn::<Empty>::burn(&denom, deps.as_mut(), &env, Uint128::from(1000u128)).unwrap();
+        let _: Response = BurnFrom::<Empty>::burn_from(
+            &denom,
+            deps.as_mut(),
+            &env,
+            &recipient,
+            Uint128::from(500u128),
+        )
+        .unwrap();
+        assert_eq!(
+            denom.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::from(3500u128)
+        );
+
+        // Can't burn more than the tracked supply
+        Burn::<Empty>::burn(&denom, deps.as_mut(), &env, Uint128::from(3501u128)).unwrap_err();
+    }
+
+    #[test]
+    fn transfer() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+        let amount = Uint128::from(1000u128);
+
+        let res: Response = denom
+            .transfer(deps.as_mut(), &env, &recipient, amount)
+            .unwrap();
+
+        // Assert that a bank send message is returned
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: denom.to_string(),
+                    amount,
+                }],
+            })
+        );
+
+        // Assert correct event
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("apollo/cw-vault-token/osmosis").add_attributes(vec![
+                    attr("action", "transfer"),
+                    attr("denom", denom.to_string()),
+                    attr("amount", amount.to_string()),
+                    attr("recipient", recipient.to_string()),
+                ])
+            ]
+        );
+    }
+
+    #[test]
+    fn from_native_denom_invalid() {
+        let res = OsmosisDenom::from_native_denom("wrong/sender/subdenom").unwrap_err();
+        assert_eq!(
+            res,
+            CwTokenError::InvalidDenom {
+                denom: "wrong/sender/subdenom".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn zero_amount() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+
+        let res = Mint::<Empty>::mint(&denom, deps.as_mut(), &env, &recipient, Uint128::zero())
+            .unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+        let res = Burn::<Empty>::burn(&denom, deps.as_mut(), &env, Uint128::zero()).unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+        let res =
+            Transfer::<Empty>::transfer(&denom, deps.as_mut(), &env, &recipient, Uint128::zero())
+                .unwrap_err();
+        assert_eq!(res, CwTokenError::ZeroAmount {});
+    }
+
+    #[test]
+    fn receive_insufficient_funds() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let info = MessageInfo {
+            sender: Addr::unchecked(SENDER),
+            funds: vec![Coin::new(999, denom.to_string())],
+        };
+
+        let res = denom
+            .receive(deps.as_mut(), &env, &info, Uint128::from(1000u128))
+            .unwrap_err();
+        assert_eq!(
+            res,
+            CwTokenError::InsufficientFunds {
+                expected: Coin::new(1000, denom.to_string()),
+                received: Coin::new(999, denom.to_string()),
+            }
+        );
+    }
+
+    #[test_case(ReceiveMode::Strict, vec![Coin::new(1000, "vault")] => Ok(vec![]) ; "strict exact amount")]
+    #[test_case(ReceiveMode::Strict, vec![Coin::new(600, "vault"), Coin::new(400, "vault")] => Ok(vec![]) ; "strict sums same denom")]
+    #[test_case(ReceiveMode::Strict, vec![Coin::new(1000, "vault"), Coin::new(1, "uosmo")] => matches Err(CwTokenError::UnexpectedFunds { .. }) ; "strict rejects other denoms")]
+    #[test_case(ReceiveMode::Strict, vec![Coin::new(1001, "vault")] => matches Err(CwTokenError::UnexpectedFunds { .. }) ; "strict rejects excess")]
+    #[test_case(ReceiveMode::Strict, vec![Coin::new(999, "vault")] => matches Err(CwTokenError::InsufficientFunds { .. }) ; "strict rejects too little")]
+    #[test_case(ReceiveMode::Lenient, vec![Coin::new(600, "vault"), Coin::new(400, "vault")] => Ok(vec![]) ; "lenient sums same denom")]
+    #[test_case(ReceiveMode::Lenient, vec![Coin::new(1, "uosmo"), Coin::new(1000, "vault"), Coin::new(2, "uatom")] => Ok(vec![Coin::new(1, "uosmo"), Coin::new(2, "uatom")]) ; "lenient returns other denoms")]
+    #[test_case(ReceiveMode::Lenient, vec![Coin::new(1, "uosmo"), Coin::new(1001, "vault")] => matches Err(CwTokenError::UnexpectedFunds { .. }) ; "lenient rejects excess")]
+    #[test_case(ReceiveMode::Lenient, vec![Coin::new(1, "uosmo")] => matches Err(CwTokenError::InsufficientFunds { .. }) ; "lenient rejects too little")]
+    fn receive_mode(mode: ReceiveMode, funds: Vec<Coin>) -> CwTokenResult<Vec<Coin>> {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let denom =
+            OsmosisDenom::new(SENDER.to_string(), SUBDENOM.to_string()).with_receive_mode(mode);
+
+        // Replace the "vault" placeholder with the full denom
+        let funds = funds
+            .into_iter()
+            .map(|c| match c.denom.as_str() {
+                "vault" => Coin::new(c.amount.u128(), denom.to_string()),
+                _ => c,
+            })
+            .collect();
+        let info = MessageInfo {
+            sender: Addr::unchecked(SENDER),
+            funds,
+        };
+
+        denom
+            .receive(deps.as_mut(), &env, &info, Uint128::from(1000u128))
+            .map(|leftover| {
+                leftover
+                    .into_iter()
+                    .map(|c| match c.denom == denom.to_string() {
+                        true => Coin::new(c.amount.u128(), "vault"),
+                        false => c,
+                    })
+                    .collect()
+            })
+    }
+
     #[test_case(Uint128::from(1000u128), Uint128::from(1000u128) ; "sent amount correct")]
     #[test_case(Uint128::from(1000u128), Uint128::from(1001u128) => panics ; "sent amount too large")]
     #[test_case(Uint128::from(1000u128), Uint128::from(999u128) => panics ; "sent amount too small")]
@@ -225,4 +1048,38 @@ mod test {
             .receive(deps.as_mut(), &env, &info, receive_coin.amount)
             .unwrap();
     }
+
+    #[test]
+    fn custom_msg_and_query() {
+        let env = mock_env();
+        let denom = OsmosisDenom::new(env.contract.address.to_string(), SUBDENOM.to_string());
+        let recipient = Addr::unchecked("recipient");
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::<TestQuery>::new(&[(
+                recipient.as_str(),
+                &[Coin::new(1000, denom.to_string())],
+            )]),
+            custom_query_type: PhantomData::<TestQuery>,
+        };
+
+        let res: Response<TestMsg> = denom
+            .mint(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            denom.mint_msg(&env, &recipient, Uint128::from(1000u128))
+        );
+
+        let res: Response<TestMsg> = denom
+            .transfer(deps.as_mut(), &env, &recipient, Uint128::from(1000u128))
+            .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        assert_eq!(
+            denom.query_balance(deps.as_ref(), &recipient).unwrap(),
+            Uint128::from(1000u128)
+        );
+    }
 }
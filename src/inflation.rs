@@ -0,0 +1,330 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+
+use crate::{mul_div, CwTokenError, CwTokenResult, Rounding};
+
+/// The configured [`InflationProtection`] of each vault token, keyed by the
+/// token (its denom, or the contract address for cw20 tokens).
+pub const INFLATION_PROTECTION: Map<&str, InflationProtection> =
+    Map::new("cw_vault_token/inflation_protection");
+
+/// Whether the dead shares of [`InflationProtection::DeadShares`] have been
+/// minted, keyed like [`INFLATION_PROTECTION`].
+const DEAD_SHARES_MINTED: Map<&str, bool> = Map::new("cw_vault_token/dead_shares_minted");
+
+#[cw_serde]
+#[derive(Default)]
+/// Protection against the inflation (donation) attack, in which the first
+/// depositor of an empty vault donates assets to the vault to inflate the
+/// share price and steal later deposits through rounding.
+///
+/// The protection is configured when the token is instantiated, and used by
+/// the conversion functions of [`crate::VaultTokenMath`].
+pub enum InflationProtection {
+    /// No protection. Shares are converted 1:1 to assets while the vault is
+    /// empty.
+    #[default]
+    None,
+    /// Virtual shares and assets, following the OpenZeppelin ERC-4626
+    /// implementation. The vault behaves as if it holds an extra
+    /// `10^decimals_offset` shares backed by one extra unit of assets, which
+    /// makes the attack unprofitable. Note that the share price is initially
+    /// `10^decimals_offset` shares per unit of assets.
+    VirtualShares {
+        /// The offset between the decimals of the shares and the assets.
+        decimals_offset: u8,
+    },
+    /// Dead shares. On the first [`crate::Mint::mint`], `amount` of the
+    /// minted shares are minted to the contract itself instead of the
+    /// recipient. These shares should never be burned by the contract.
+    DeadShares {
+        /// The amount of shares to lock in the contract on the first mint.
+        amount: Uint128,
+    },
+}
+
+impl InflationProtection {
+    /// ## Description
+    /// Loads the configured [`InflationProtection`] of `token` from storage.
+    /// Returns [`InflationProtection::None`] if nothing has been configured.
+    ///
+    /// ## Errors
+    /// Returns [`cosmwasm_std::StdError`] if the stored value can't be
+    /// deserialized.
+    pub fn load(storage: &dyn Storage, token: &str) -> StdResult<Self> {
+        Ok(INFLATION_PROTECTION
+            .may_load(storage, token)?
+            .unwrap_or_default())
+    }
+
+    /// ## Description
+    /// Validates the configuration and saves it to storage for `token`.
+    /// Should be called when the token is instantiated.
+    ///
+    /// ## Errors
+    /// Returns [`CwTokenError::Overflow`] if `decimals_offset` is too large
+    /// and [`CwTokenError::ZeroDeadShares`] if `amount` of dead shares is
+    /// zero.
+    pub fn save(&self, storage: &mut dyn Storage, token: &str) -> CwTokenResult<()> {
+        match self {
+            Self::VirtualShares { .. } => {
+                self.virtual_shares()?;
+            }
+            Self::DeadShares { amount } if amount.is_zero() => {
+                return Err(CwTokenError::ZeroDeadShares {});
+            }
+            _ => {}
+        }
+        Ok(INFLATION_PROTECTION.save(storage, token, self)?)
+    }
+
+    /// The amount of virtual shares, i.e. `10^decimals_offset`.
+    fn virtual_shares(&self) -> StdResult<Uint128> {
+        match self {
+            Self::VirtualShares { decimals_offset } => {
+                Ok(Uint128::new(10).checked_pow(u32::from(*decimals_offset))?)
+            }
+            _ => Ok(Uint128::zero()),
+        }
+    }
+
+    /// Returns `total_assets` and `total_supply` with the virtual assets (one
+    /// unit) and virtual shares (`10^decimals_offset`) added.
+    fn virtual_totals(
+        &self,
+        total_assets: Uint128,
+        total_supply: Uint128,
+    ) -> StdResult<(Uint128, Uint128)> {
+        Ok((
+            total_assets.checked_add(Uint128::one())?,
+            total_supply.checked_add(self.virtual_shares()?)?,
+        ))
+    }
+
+    /// ## Description
+    /// Converts an amount of `assets` to shares, taking the virtual shares
+    /// and assets into account.
+    ///
+    /// ## Errors
+    /// Returns an error if the result overflows.
+    pub fn convert_to_shares(
+        &self,
+        assets: Uint128,
+        total_assets: Uint128,
+        total_supply: Uint128,
+        rounding: Rounding,
+    ) -> CwTokenResult<Uint128> {
+        match self {
+            Self::VirtualShares { .. } => {
+                let (total_assets, total_supply) =
+                    self.virtual_totals(total_assets, total_supply)?;
+                mul_div(assets, total_supply, total_assets, rounding)
+            }
+            _ => crate::shares_for_assets(assets, total_assets, total_supply, rounding),
+        }
+    }
+
+    /// ## Description
+    /// Converts an amount of `shares` to assets, taking the virtual shares
+    /// and assets into account.
+    ///
+    /// ## Errors
+    /// Returns an error if the result overflows.
+    pub fn convert_to_assets(
+        &self,
+        shares: Uint128,
+        total_assets: Uint128,
+        total_supply: Uint128,
+        rounding: Rounding,
+    ) -> CwTokenResult<Uint128> {
+        match self {
+            Self::VirtualShares { .. } => {
+                let (total_assets, total_supply) =
+                    self.virtual_totals(total_assets, total_supply)?;
+                mul_div(shares, total_assets, total_supply, rounding)
+            }
+            _ => crate::assets_for_shares(shares, total_assets, total_supply, rounding),
+        }
+    }
+}
+
+/// ## Description
+/// Returns the amount of dead shares that should be minted to the contract
+/// out of a mint of `amount` of `token`, and records that they have been
+/// minted. Returns zero unless [`InflationProtection::DeadShares`] is
+/// configured and this is the first mint.
+///
+/// ## Errors
+/// Returns [`CwTokenError::FirstMintTooSmall`] if `amount` does not exceed
+/// the amount of dead shares.
+pub(crate) fn take_dead_shares(
+    storage: &mut dyn Storage,
+    token: &str,
+    amount: Uint128,
+) -> CwTokenResult<Uint128> {
+    let dead_shares = match InflationProtection::load(storage, token)? {
+        InflationProtection::DeadShares { amount } => amount,
+        _ => return Ok(Uint128::zero()),
+    };
+    if DEAD_SHARES_MINTED
+        .may_load(storage, token)?
+        .unwrap_or(false)
+    {
+        return Ok(Uint128::zero());
+    }
+    if amount <= dead_shares {
+        return Err(CwTokenError::FirstMintTooSmall { dead_shares });
+    }
+
+    DEAD_SHARES_MINTED.save(storage, token, &true)?;
+    Ok(dead_shares)
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+    use test_case::test_case;
+
+    use super::*;
+
+    const DENOM: &str = "factory/vault/shares";
+
+    #[test_case(InflationProtection::None => 100 ; "no protection")]
+    #[test_case(InflationProtection::VirtualShares { decimals_offset: 0 } => 100 ; "offset 0")]
+    #[test_case(InflationProtection::VirtualShares { decimals_offset: 3 } => 100_000 ; "offset 3")]
+    fn convert_empty_vault(protection: InflationProtection) -> u128 {
+        protection
+            .convert_to_shares(
+                Uint128::new(100),
+                Uint128::zero(),
+                Uint128::zero(),
+                Rounding::Down,
+            )
+            .unwrap()
+            .u128()
+    }
+
+    #[test]
+    fn virtual_shares_make_inflation_attack_unprofitable() {
+        let protection = InflationProtection::VirtualShares { decimals_offset: 3 };
+
+        // Attacker deposits 1 unit of assets and receives 1000 shares
+        let attacker_shares = protection
+            .convert_to_shares(
+                Uint128::one(),
+                Uint128::zero(),
+                Uint128::zero(),
+                Rounding::Down,
+            )
+            .unwrap();
+        assert_eq!(attacker_shares, Uint128::new(1000));
+
+        // Attacker donates 1_000_000 assets to the vault. The victim then
+        // deposits 500_000 assets and still receives shares.
+        let total_assets = Uint128::new(1_000_001);
+        let victim_shares = protection
+            .convert_to_shares(
+                Uint128::new(500_000),
+                total_assets,
+                attacker_shares,
+                Rounding::Down,
+            )
+            .unwrap();
+        assert_eq!(victim_shares, Uint128::new(999));
+
+        // The attacker redeems at a loss
+        let total_assets = total_assets + Uint128::new(500_000);
+        let total_supply = attacker_shares + victim_shares;
+        let attacker_assets = protection
+            .convert_to_assets(attacker_shares, total_assets, total_supply, Rounding::Down)
+            .unwrap();
+        assert!(attacker_assets < Uint128::new(1_000_001));
+    }
+
+    #[test]
+    fn save_validates_config() {
+        let mut storage = MockStorage::new();
+
+        InflationProtection::VirtualShares {
+            decimals_offset: 39,
+        }
+        .save(&mut storage, DENOM)
+        .unwrap_err();
+        let err = InflationProtection::DeadShares {
+            amount: Uint128::zero(),
+        }
+        .save(&mut storage, DENOM)
+        .unwrap_err();
+        assert_eq!(err, CwTokenError::ZeroDeadShares {});
+        assert_eq!(
+            InflationProtection::load(&storage, DENOM).unwrap(),
+            InflationProtection::None
+        );
+    }
+
+    #[test]
+    fn dead_shares_are_taken_once() {
+        let mut storage = MockStorage::new();
+
+        // Without dead shares nothing is taken
+        assert_eq!(
+            take_dead_shares(&mut storage, DENOM, Uint128::new(1000)).unwrap(),
+            Uint128::zero()
+        );
+
+        InflationProtection::DeadShares {
+            amount: Uint128::new(1000),
+        }
+        .save(&mut storage, DENOM)
+        .unwrap();
+
+        // First mint must be larger than the dead shares
+        assert_eq!(
+            take_dead_shares(&mut storage, DENOM, Uint128::new(1000)).unwrap_err(),
+            CwTokenError::FirstMintTooSmall {
+                dead_shares: Uint128::new(1000)
+            }
+        );
+
+        assert_eq!(
+            take_dead_shares(&mut storage, DENOM, Uint128::new(1001)).unwrap(),
+            Uint128::new(1000)
+        );
+        assert_eq!(
+            take_dead_shares(&mut storage, DENOM, Uint128::new(1001)).unwrap(),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn config_is_stored_per_token() {
+        let mut storage = MockStorage::new();
+        let other = "factory/vault/other";
+
+        InflationProtection::DeadShares {
+            amount: Uint128::new(1000),
+        }
+        .save(&mut storage, DENOM)
+        .unwrap();
+        assert_eq!(
+            InflationProtection::load(&storage, other).unwrap(),
+            InflationProtection::None
+        );
+
+        InflationProtection::DeadShares {
+            amount: Uint128::new(500),
+        }
+        .save(&mut storage, other)
+        .unwrap();
+        assert_eq!(
+            take_dead_shares(&mut storage, DENOM, Uint128::new(1001)).unwrap(),
+            Uint128::new(1000)
+        );
+        // Taking the dead shares of one token does not affect the other
+        assert_eq!(
+            take_dead_shares(&mut storage, other, Uint128::new(501)).unwrap(),
+            Uint128::new(500)
+        );
+    }
+}
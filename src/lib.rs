@@ -9,8 +9,9 @@
 //!
 //! An abstraction for different ways of implementing a vault token.
 //! This crate defines a set of traits that define the behavior of a vault
-//! token. Two implementations are provided, one for an Osmosis native denom
-//! minted through the TokenFactory module and one for Cw4626 tokenized vaults.
+//! token. Implementations are provided for native denoms minted through the
+//! TokenFactory modules of Osmosis, Neutron, Injective and Kujira, and for
+//! Cw4626 tokenized vaults.
 //! See the cosmwasm-vault-standard crate for more information about tokenized
 //! vaults.
 
@@ -20,9 +21,25 @@ mod error;
 /// CW4626 and Denom impl
 mod implementations;
 
+/// Inflation attack protection
+mod inflation;
+
+/// Share and asset conversion math
+mod math;
+
+/// Total supply backends of the native token implementations
+mod supply;
+
+/// Test helpers for contracts using the vault token implementations
+#[cfg(feature = "testing")]
+pub mod testing;
+
 /// Traits functionality interface
 mod traits;
 
 pub use error::*;
 pub use implementations::*;
+pub use inflation::*;
+pub use math::*;
+pub use supply::*;
 pub use traits::*;
@@ -0,0 +1,475 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CustomQuery, Deps, Empty, Uint128, Uint256};
+
+use crate::{CwTokenError, CwTokenResult, Query};
+
+#[cw_serde]
+#[derive(Copy, Eq)]
+/// The direction in which to round the result of a share/asset conversion.
+///
+/// To protect the vault, conversions should always round against the user:
+/// - Down when computing shares to mint for deposited assets, or assets to
+///   return for redeemed shares.
+/// - Up when computing assets required to mint shares, or shares to burn for
+///   withdrawn assets.
+pub enum Rounding {
+    /// Round towards zero (floor).
+    Down,
+    /// Round away from zero (ceil).
+    Up,
+}
+
+/// ## Description
+/// Calculates `x * numerator / denominator` using [`Uint256`] intermediates
+/// so that the multiplication can't overflow, rounding the result according
+/// to `rounding`.
+///
+/// ## Errors
+/// Returns [`crate::CwTokenError::DivideByZero`] if `denominator` is zero, and
+/// [`crate::CwTokenError::ConversionOverflow`] if the result does not fit in a
+/// [`Uint128`].
+pub fn mul_div(
+    x: Uint128,
+    numerator: Uint128,
+    denominator: Uint128,
+    rounding: Rounding,
+) -> CwTokenResult<Uint128> {
+    let product = x.full_mul(numerator);
+    let denominator = Uint256::from(denominator);
+
+    let mut result = product.checked_div(denominator)?;
+    if rounding == Rounding::Up && !product.checked_rem(denominator)?.is_zero() {
+        result += Uint256::one();
+    }
+
+    Ok(Uint128::try_from(result)?)
+}
+
+/// ## Description
+/// Converts an amount of `assets` to the amount of shares they are worth,
+/// given the vault's `total_assets` and the vault token's `total_supply`.
+/// If no shares have been minted yet, shares are converted 1:1 to assets.
+///
+/// ## Errors
+/// Returns an error if `total_assets` is zero while `total_supply` is not,
+/// or if the result overflows.
+pub fn shares_for_assets(
+    assets: Uint128,
+    total_assets: Uint128,
+    total_supply: Uint128,
+    rounding: Rounding,
+) -> CwTokenResult<Uint128> {
+    if total_supply.is_zero() {
+        return Ok(assets);
+    }
+    mul_div(assets, total_supply, total_assets, rounding)
+}
+
+/// ## Description
+/// Converts an amount of `shares` to the amount of assets they are worth,
+/// given the vault's `total_assets` and the vault token's `total_supply`.
+/// If no shares have been minted yet, shares are converted 1:1 to assets.
+///
+/// ## Errors
+/// Returns an error if the result overflows.
+pub fn assets_for_shares(
+    shares: Uint128,
+    total_assets: Uint128,
+    total_supply: Uint128,
+    rounding: Rounding,
+) -> CwTokenResult<Uint128> {
+    if total_supply.is_zero() {
+        return Ok(shares);
+    }
+    mul_div(shares, total_assets, total_supply, rounding)
+}
+
+/// Share/asset conversion functions following the ERC-4626 standard, for any
+/// [`crate::VaultToken`]. The total supply is queried through
+/// [`Query::query_total_supply`], while the vault's `total_assets` must be
+/// supplied by the caller. The conversions take the
+/// [`crate::InflationProtection`] configured at instantiation into account.
+///
+/// This trait is implemented for all types implementing [`Query`].
+pub trait VaultTokenMath<Q: CustomQuery = Empty>: Query<Q> {
+    /// ## Description
+    /// Converts `assets` to shares at the current share price, rounding
+    /// according to `rounding`.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn convert_to_shares(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        assets: Uint128,
+        rounding: Rounding,
+    ) -> CwTokenResult<Uint128> {
+        let total_supply = self.query_total_supply(deps)?;
+        self.query_inflation_protection(deps)?.convert_to_shares(
+            assets,
+            total_assets,
+            total_supply,
+            rounding,
+        )
+    }
+
+    /// ## Description
+    /// Converts `shares` to assets at the current share price, rounding
+    /// according to `rounding`.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn convert_to_assets(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        shares: Uint128,
+        rounding: Rounding,
+    ) -> CwTokenResult<Uint128> {
+        let total_supply = self.query_total_supply(deps)?;
+        self.query_inflation_protection(deps)?.convert_to_assets(
+            shares,
+            total_assets,
+            total_supply,
+            rounding,
+        )
+    }
+
+    /// ## Description
+    /// Returns the amount of shares that would be minted for depositing
+    /// `assets`. Rounds down.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn preview_deposit(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        assets: Uint128,
+    ) -> CwTokenResult<Uint128> {
+        self.convert_to_shares(deps, total_assets, assets, Rounding::Down)
+    }
+
+    /// ## Description
+    /// Returns the amount of assets that must be deposited to mint `shares`.
+    /// Rounds up.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn preview_mint(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        shares: Uint128,
+    ) -> CwTokenResult<Uint128> {
+        self.convert_to_assets(deps, total_assets, shares, Rounding::Up)
+    }
+
+    /// ## Description
+    /// Returns the amount of shares that must be burned to withdraw `assets`.
+    /// Rounds up.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn preview_withdraw(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        assets: Uint128,
+    ) -> CwTokenResult<Uint128> {
+        self.convert_to_shares(deps, total_assets, assets, Rounding::Up)
+    }
+
+    /// ## Description
+    /// Returns the amount of assets that would be returned for redeeming
+    /// `shares`. Rounds down.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn preview_redeem(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        shares: Uint128,
+    ) -> CwTokenResult<Uint128> {
+        self.convert_to_assets(deps, total_assets, shares, Rounding::Down)
+    }
+
+    /// ## Description
+    /// Returns the maximum amount of shares that can still be minted before
+    /// the total supply overflows.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn max_mint(&self, deps: Deps<Q>) -> CwTokenResult<Uint128> {
+        Ok(Uint128::MAX - self.query_total_supply(deps)?)
+    }
+
+    /// ## Description
+    /// Returns the maximum amount of assets that can be deposited, i.e. the
+    /// assets worth [`VaultTokenMath::max_mint`] shares. Saturates at
+    /// [`Uint128::MAX`] if the conversion overflows.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`], e.g. if a query fails.
+    fn max_deposit(&self, deps: Deps<Q>, total_assets: Uint128) -> CwTokenResult<Uint128> {
+        let max_mint = self.max_mint(deps)?;
+        match self.convert_to_assets(deps, total_assets, max_mint, Rounding::Down) {
+            Err(CwTokenError::Overflow(_) | CwTokenError::ConversionOverflow(_)) => {
+                Ok(Uint128::MAX)
+            }
+            res => res,
+        }
+    }
+
+    /// ## Description
+    /// Returns the maximum amount of assets that `owner` can withdraw, i.e.
+    /// the assets worth the owner's full balance of shares. Rounds down.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn max_withdraw<A: Into<String>>(
+        &self,
+        deps: Deps<Q>,
+        total_assets: Uint128,
+        owner: A,
+    ) -> CwTokenResult<Uint128> {
+        let shares = self.query_balance(deps, owner)?;
+        self.convert_to_assets(deps, total_assets, shares, Rounding::Down)
+    }
+
+    /// ## Description
+    /// Returns the maximum amount of shares that `owner` can redeem, i.e. the
+    /// owner's balance of shares.
+    ///
+    /// ## Errors
+    /// May return [`crate::CwTokenError`].
+    fn max_redeem<A: Into<String>>(&self, deps: Deps<Q>, owner: A) -> CwTokenResult<Uint128> {
+        self.query_balance(deps, owner)
+    }
+}
+
+impl<T: Query<Q>, Q: CustomQuery> VaultTokenMath<Q> for T {}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Addr, Response, StdError};
+    use test_case::test_case;
+
+    use crate::cw4626::{Cw4626, Cw4626InstantiateMsg};
+    use crate::{InflationProtection, Instantiate, Mint};
+
+    use super::*;
+
+    #[test_case(10, 3, 4, Rounding::Down => 7 ; "round down")]
+    #[test_case(10, 3, 4, Rounding::Up => 8 ; "round up")]
+    #[test_case(10, 4, 5, Rounding::Up => 8 ; "exact result is not rounded up")]
+    #[test_case(0, 3, 4, Rounding::Up => 0 ; "zero")]
+    fn test_mul_div(x: u128, numerator: u128, denominator: u128, rounding: Rounding) -> u128 {
+        mul_div(
+            Uint128::new(x),
+            Uint128::new(numerator),
+            Uint128::new(denominator),
+            rounding,
+        )
+        .unwrap()
+        .u128()
+    }
+
+    #[test]
+    fn test_mul_div_uses_uint256_intermediates() {
+        // u128::MAX * 2 would overflow a Uint128
+        let res = mul_div(
+            Uint128::MAX,
+            Uint128::new(2),
+            Uint128::new(4),
+            Rounding::Down,
+        )
+        .unwrap();
+        assert_eq!(res, Uint128::MAX / Uint128::new(2));
+    }
+
+    #[test]
+    fn test_mul_div_errors() {
+        // Divide by zero
+        let res = mul_div(
+            Uint128::one(),
+            Uint128::one(),
+            Uint128::zero(),
+            Rounding::Down,
+        );
+        assert!(matches!(res, Err(CwTokenError::DivideByZero(..))));
+
+        // Result too large for a Uint128
+        let res = mul_div(
+            Uint128::MAX,
+            Uint128::new(2),
+            Uint128::one(),
+            Rounding::Down,
+        );
+        assert!(matches!(res, Err(CwTokenError::ConversionOverflow(..))));
+    }
+
+    #[test_case(100, 0, 0, Rounding::Down => 100 ; "empty vault converts 1:1")]
+    #[test_case(100, 300, 200, Rounding::Down => 66 ; "round down")]
+    #[test_case(100, 300, 200, Rounding::Up => 67 ; "round up")]
+    fn test_shares_for_assets(
+        assets: u128,
+        total_assets: u128,
+        total_supply: u128,
+        rounding: Rounding,
+    ) -> u128 {
+        shares_for_assets(
+            Uint128::new(assets),
+            Uint128::new(total_assets),
+            Uint128::new(total_supply),
+            rounding,
+        )
+        .unwrap()
+        .u128()
+    }
+
+    #[test_case(100, 0, 0, Rounding::Down => 100 ; "empty vault converts 1:1")]
+    #[test_case(100, 200, 300, Rounding::Down => 66 ; "round down")]
+    #[test_case(100, 200, 300, Rounding::Up => 67 ; "round up")]
+    fn test_assets_for_shares(
+        shares: u128,
+        total_assets: u128,
+        total_supply: u128,
+        rounding: Rounding,
+    ) -> u128 {
+        assets_for_shares(
+            Uint128::new(shares),
+            Uint128::new(total_assets),
+            Uint128::new(total_supply),
+            rounding,
+        )
+        .unwrap()
+        .u128()
+    }
+
+    /// A token with a fixed total supply, whose inflation protection query
+    /// fails if `broken` is set.
+    struct StubToken {
+        total_supply: Uint128,
+        broken: bool,
+    }
+
+    impl Query for StubToken {
+        fn query_balance<A: Into<String>>(
+            &self,
+            _deps: Deps,
+            _address: A,
+        ) -> CwTokenResult<Uint128> {
+            Ok(Uint128::zero())
+        }
+
+        fn query_total_supply(&self, _deps: Deps) -> CwTokenResult<Uint128> {
+            Ok(self.total_supply)
+        }
+
+        fn query_inflation_protection(&self, _deps: Deps) -> CwTokenResult<InflationProtection> {
+            match self.broken {
+                true => Err(StdError::generic_err("query failed").into()),
+                false => Ok(InflationProtection::None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_deposit_saturates_on_overflow() {
+        let deps = mock_dependencies();
+        let token = StubToken {
+            total_supply: Uint128::one(),
+            broken: false,
+        };
+        assert_eq!(
+            token.max_deposit(deps.as_ref(), Uint128::MAX).unwrap(),
+            Uint128::MAX
+        );
+    }
+
+    #[test]
+    fn test_max_deposit_propagates_query_errors() {
+        let deps = mock_dependencies();
+        let token = StubToken {
+            total_supply: Uint128::one(),
+            broken: true,
+        };
+        assert_eq!(
+            token.max_deposit(deps.as_ref(), Uint128::MAX).unwrap_err(),
+            CwTokenError::Std(StdError::generic_err("query failed"))
+        );
+    }
+
+    #[test]
+    fn test_vault_token_math() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let owner = Addr::unchecked("owner");
+        let cw4626 = Cw4626::new(&env);
+
+        let init_info = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        };
+        let _: Response = cw4626.instantiate(deps.as_mut(), init_info).unwrap();
+        let _: Response = cw4626
+            .mint(deps.as_mut(), &env, &owner, Uint128::new(300))
+            .unwrap();
+
+        // 300 shares backed by 200 assets
+        let total_assets = Uint128::new(200);
+        let deps = deps.as_ref();
+
+        assert_eq!(
+            cw4626
+                .preview_deposit(deps, total_assets, Uint128::new(100))
+                .unwrap(),
+            Uint128::new(150)
+        );
+        assert_eq!(
+            cw4626
+                .preview_mint(deps, total_assets, Uint128::new(100))
+                .unwrap(),
+            Uint128::new(67)
+        );
+        assert_eq!(
+            cw4626
+                .preview_withdraw(deps, total_assets, Uint128::new(101))
+                .unwrap(),
+            Uint128::new(152)
+        );
+        assert_eq!(
+            cw4626
+                .preview_redeem(deps, total_assets, Uint128::new(100))
+                .unwrap(),
+            Uint128::new(66)
+        );
+        assert_eq!(
+            cw4626.max_withdraw(deps, total_assets, &owner).unwrap(),
+            total_assets
+        );
+        assert_eq!(cw4626.max_redeem(deps, &owner).unwrap(), Uint128::new(300));
+        assert_eq!(
+            cw4626.max_mint(deps).unwrap(),
+            Uint128::MAX - Uint128::new(300)
+        );
+        assert_eq!(
+            cw4626.max_deposit(deps, total_assets).unwrap(),
+            assets_for_shares(
+                Uint128::MAX - Uint128::new(300),
+                total_assets,
+                Uint128::new(300),
+                Rounding::Down
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CustomQuery, Deps, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use osmosis_std::types::cosmos::bank::v1beta1::BankQuerier;
+use std::str::FromStr;
+
+use crate::CwTokenResult;
+
+/// The total supply of each native token using [`SupplyBackend::Tracked`], by
+/// denom.
+pub const TRACKED_SUPPLY: Map<&str, Uint128> = Map::new("cw_vault_token/tracked_supply");
+
+#[cw_serde]
+#[derive(Default, Copy)]
+/// How the native token implementations query their total supply. The backend
+/// is chosen per token, depending on which queries the chain supports.
+pub enum SupplyBackend {
+    /// The stargate query `/cosmos.bank.v1beta1.Query/SupplyOf`. Fails on
+    /// chains that don't whitelist it.
+    #[default]
+    Stargate,
+    /// The `BankQuery::Supply` query of CosmWasm 1.1. Requires the
+    /// `cosmwasm_1_1` feature, and the chain to support the `cosmwasm_1_1`
+    /// capability.
+    #[cfg(feature = "cosmwasm_1_1")]
+    Bank,
+    /// A counter in contract storage, updated on every mint and burn. Does
+    /// not require any bank supply query, but only counts tokens minted and
+    /// burned through this crate, so the backend must be used from the
+    /// moment the token is instantiated.
+    Tracked,
+}
+
+impl SupplyBackend {
+    /// ## Description
+    /// Queries the total supply of `denom` using the backend.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the query fails.
+    pub fn query_total_supply<Q: CustomQuery>(
+        self,
+        deps: Deps<Q>,
+        denom: String,
+    ) -> CwTokenResult<Uint128> {
+        match self {
+            Self::Stargate => {
+                let amount_str = BankQuerier::new(&deps.querier)
+                    .supply_of(denom)?
+                    .amount
+                    .map(|c| c.amount)
+                    .ok_or_else(|| StdError::not_found("amount in supply response"))?;
+                Ok(Uint128::from_str(&amount_str)?)
+            }
+            #[cfg(feature = "cosmwasm_1_1")]
+            Self::Bank => Ok(deps.querier.query_supply(denom)?.amount),
+            Self::Tracked => Ok(TRACKED_SUPPLY
+                .may_load(deps.storage, &denom)?
+                .unwrap_or_default()),
+        }
+    }
+
+    /// Records that `amount` tokens of `denom` were minted. Does nothing
+    /// unless the backend is [`SupplyBackend::Tracked`].
+    pub(crate) fn record_mint(
+        self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        if let Self::Tracked = self {
+            let supply = TRACKED_SUPPLY.may_load(storage, denom)?.unwrap_or_default();
+            TRACKED_SUPPLY.save(storage, denom, &supply.checked_add(amount)?)?;
+        }
+        Ok(())
+    }
+
+    /// Records that `amount` tokens of `denom` were burned. Does nothing
+    /// unless the backend is [`SupplyBackend::Tracked`].
+    pub(crate) fn record_burn(
+        self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        if let Self::Tracked = self {
+            let supply = TRACKED_SUPPLY.may_load(storage, denom)?.unwrap_or_default();
+            TRACKED_SUPPLY.save(storage, denom, &supply.checked_sub(amount)?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::mock_dependencies;
+
+    use super::*;
+
+    #[test]
+    fn tracked_supply() {
+        let mut deps = mock_dependencies();
+        let backend = SupplyBackend::Tracked;
+
+        assert_eq!(
+            backend
+                .query_total_supply(deps.as_ref(), "denom".to_string())
+                .unwrap(),
+            Uint128::zero()
+        );
+
+        backend
+            .record_mint(&mut deps.storage, "denom", Uint128::new(1000))
+            .unwrap();
+        backend
+            .record_burn(&mut deps.storage, "denom", Uint128::new(400))
+            .unwrap();
+        assert_eq!(
+            backend
+                .query_total_supply(deps.as_ref(), "denom".to_string())
+                .unwrap(),
+            Uint128::new(600)
+        );
+
+        // The supply of each denom is tracked separately
+        backend
+            .record_mint(&mut deps.storage, "other", Uint128::new(50))
+            .unwrap();
+        assert_eq!(
+            backend
+                .query_total_supply(deps.as_ref(), "other".to_string())
+                .unwrap(),
+            Uint128::new(50)
+        );
+        assert_eq!(
+            backend
+                .query_total_supply(deps.as_ref(), "denom".to_string())
+                .unwrap(),
+            Uint128::new(600)
+        );
+
+        // Burning more than the supply underflows
+        backend
+            .record_burn(&mut deps.storage, "denom", Uint128::new(601))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn other_backends_do_not_track() {
+        let mut deps = mock_dependencies();
+
+        SupplyBackend::Stargate
+            .record_mint(&mut deps.storage, "denom", Uint128::new(1000))
+            .unwrap();
+        assert_eq!(
+            TRACKED_SUPPLY.may_load(&deps.storage, "denom").unwrap(),
+            None
+        );
+    }
+
+    #[cfg(feature = "cosmwasm_1_1")]
+    #[test]
+    fn bank_supply() {
+        use cosmwasm_std::testing::mock_dependencies_with_balances;
+        use cosmwasm_std::Coin;
+
+        let deps = mock_dependencies_with_balances(&[
+            ("alice", &[Coin::new(1000, "denom")]),
+            ("bob", &[Coin::new(500, "denom")]),
+        ]);
+        assert_eq!(
+            SupplyBackend::Bank
+                .query_total_supply(deps.as_ref(), "denom".to_string())
+                .unwrap(),
+            Uint128::new(1500)
+        );
+    }
+}
@@ -0,0 +1,828 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, CustomMsg, CustomQuery, Empty, Env, MessageInfo, OwnedDeps,
+    Response, StdResult, Uint128,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as CoinMsg;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgCreateDenom, MsgMint};
+
+use crate::cw4626::{Cw4626, Cw4626InstantiateMsg};
+use crate::kujira::{DenomMsg, KujiraDenom, KujiraMsg};
+use crate::neutron::{NeutronDenom, NeutronMsg};
+use crate::osmosis::OsmosisDenom;
+use crate::testing::MockTokenFactoryQuerier;
+use crate::{
+    Burn, CwTokenError, CwTokenResponse, Instantiate, Mint, Query, Receive, Transfer, VaultToken,
+};
+
+/// The mock dependencies used by the conformance suite.
+pub type MockTokenDeps<Q = Empty> = OwnedDeps<MockStorage, MockApi, MockTokenFactoryQuerier, Q>;
+
+const USER: &str = "conformance_user";
+const WRONG_DENOM: &str = "conformance/wrong";
+
+/// Sets up a [`VaultToken`] implementation for the conformance suite. See
+/// [`run_conformance_suite`].
+pub trait VaultTokenHarness {
+    /// The custom message type of the responses.
+    type CustomMsg: CustomMsg;
+    /// The custom query type of the dependencies.
+    type CustomQuery: CustomQuery;
+    /// The implementation under test.
+    type Token: VaultToken<Self::CustomMsg, Self::CustomQuery>;
+
+    /// Returns mock dependencies, an env and an instantiated token that the
+    /// contract `env.contract.address` can mint and burn. The response of the
+    /// instantiation must already have been applied to the querier's ledger.
+    fn setup(&self) -> (MockTokenDeps<Self::CustomQuery>, Env, Self::Token);
+
+    /// Prepares a call to [`crate::Receive::receive`] in which `sender` sends
+    /// `amount` tokens to the contract, and returns its [`MessageInfo`]. The
+    /// default implementation is for native tokens: it sends `amount` of the
+    /// token's denom from `sender` to the contract in the ledger, as the chain
+    /// would before executing the message, and includes it in `info.funds`.
+    fn receive_info(
+        &self,
+        deps: &mut MockTokenDeps<Self::CustomQuery>,
+        env: &Env,
+        token: &Self::Token,
+        sender: &Addr,
+        amount: Uint128,
+    ) -> MessageInfo {
+        send_funds(deps, env, sender, token.to_string(), amount)
+    }
+
+    /// Prepares a call to [`crate::Receive::receive`] in which `sender` sends
+    /// `amount` of some other denom instead of the token, or returns `None`
+    /// if the token has no denom. The default implementation is for native
+    /// tokens.
+    fn wrong_denom_info(
+        &self,
+        deps: &mut MockTokenDeps<Self::CustomQuery>,
+        env: &Env,
+        _token: &Self::Token,
+        sender: &Addr,
+        amount: Uint128,
+    ) -> Option<MessageInfo> {
+        deps.querier
+            .set_balance(sender, Coin::new(amount.u128(), WRONG_DENOM));
+        Some(send_funds(
+            deps,
+            env,
+            sender,
+            WRONG_DENOM.to_string(),
+            amount,
+        ))
+    }
+
+    /// Applies the messages of `res`, sent by the contract
+    /// `env.contract.address`, to the querier's ledger. Must be atomic like a
+    /// transaction, i.e. leave the ledger unchanged if any message fails. The
+    /// default implementation applies the bank and Osmosis token factory
+    /// messages understood by [`MockTokenFactoryQuerier::apply_response`].
+    /// Implementations using other messages, e.g. custom messages, must
+    /// translate them, see [`translate_response`].
+    ///
+    /// ## Errors
+    /// Returns an error if any of the messages would fail on chain.
+    fn apply_response(
+        &self,
+        deps: &mut MockTokenDeps<Self::CustomQuery>,
+        env: &Env,
+        res: &Response<Self::CustomMsg>,
+    ) -> StdResult<()> {
+        deps.querier.apply_response(env, res)
+    }
+
+    /// Returns the total supply of `token`, used to check the supply
+    /// invariants. The default implementation uses
+    /// [`Query::query_total_supply`], which must then be answered by the
+    /// querier.
+    fn total_supply(
+        &self,
+        deps: &MockTokenDeps<Self::CustomQuery>,
+        token: &Self::Token,
+    ) -> Uint128 {
+        token
+            .query_total_supply(deps.as_ref())
+            .expect("total supply query failed")
+    }
+}
+
+/// [`VaultTokenHarness`] for [`OsmosisDenom`]. Use
+/// [`OsmosisDenomHarness::new`] for the default `Empty` custom types, or
+/// `OsmosisDenomHarness::<C, Q>::default()` otherwise.
+pub struct OsmosisDenomHarness<C = Empty, Q = Empty>(PhantomData<(C, Q)>);
+
+impl OsmosisDenomHarness {
+    /// Creates a harness using `Empty` custom messages and queries.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C, Q> Default for OsmosisDenomHarness<C, Q> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> VaultTokenHarness for OsmosisDenomHarness<C, Q> {
+    type CustomMsg = C;
+    type CustomQuery = Q;
+    type Token = OsmosisDenom;
+
+    fn setup(&self) -> (MockTokenDeps<Q>, Env, OsmosisDenom) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let token = OsmosisDenom::new(env.contract.address.to_string(), "vault".to_string());
+
+        let res: Response<C> = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+
+        (deps, env, token)
+    }
+}
+
+/// [`VaultTokenHarness`] for [`Cw4626`]. Use [`Cw4626Harness::new`] for the
+/// default `Empty` custom types, or `Cw4626Harness::<C, Q>::default()`
+/// otherwise.
+pub struct Cw4626Harness<C = Empty, Q = Empty>(PhantomData<(C, Q)>);
+
+impl Cw4626Harness {
+    /// Creates a harness using `Empty` custom messages and queries.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C, Q> Default for Cw4626Harness<C, Q> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C: CustomMsg, Q: CustomQuery> VaultTokenHarness for Cw4626Harness<C, Q> {
+    type CustomMsg = C;
+    type CustomQuery = Q;
+    type Token = Cw4626;
+
+    fn setup(&self) -> (MockTokenDeps<Q>, Env, Cw4626) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let token = Cw4626::new(&env);
+
+        let msg = Cw4626InstantiateMsg {
+            name: "Cw4626 tokenized vault".to_string(),
+            symbol: "vaultToken".to_string(),
+            decimals: 6,
+            marketing: None,
+            inflation_protection: None,
+            snapshots: false,
+        };
+        let _: Response<C> = token.instantiate(deps.as_mut(), msg).unwrap();
+
+        (deps, env, token)
+    }
+
+    /// Cw4626 tokens are received from the sender's cw20 balance, so no funds
+    /// are sent.
+    fn receive_info(
+        &self,
+        _deps: &mut MockTokenDeps<Q>,
+        _env: &Env,
+        _token: &Cw4626,
+        sender: &Addr,
+        _amount: Uint128,
+    ) -> MessageInfo {
+        MessageInfo {
+            sender: sender.clone(),
+            funds: vec![],
+        }
+    }
+
+    /// Cw4626 tokens have no denom, so the check is skipped.
+    fn wrong_denom_info(
+        &self,
+        _deps: &mut MockTokenDeps<Q>,
+        _env: &Env,
+        _token: &Cw4626,
+        _sender: &Addr,
+        _amount: Uint128,
+    ) -> Option<MessageInfo> {
+        None
+    }
+}
+
+/// [`VaultTokenHarness`] for [`NeutronDenom`] with the crate's [`NeutronMsg`]
+/// as custom message type. The messages are applied to the ledger as the
+/// equivalent Osmosis token factory messages.
+#[derive(Default)]
+pub struct NeutronDenomHarness;
+
+impl VaultTokenHarness for NeutronDenomHarness {
+    type CustomMsg = NeutronMsg;
+    type CustomQuery = Empty;
+    type Token = NeutronDenom;
+
+    fn setup(&self) -> (MockTokenDeps, Env, NeutronDenom) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let token = NeutronDenom::new(env.contract.address.to_string(), "vault".to_string());
+
+        let res: Response<NeutronMsg> = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        self.apply_response(&mut deps, &env, &res).unwrap();
+
+        (deps, env, token)
+    }
+
+    fn apply_response(
+        &self,
+        deps: &mut MockTokenDeps,
+        env: &Env,
+        res: &Response<NeutronMsg>,
+    ) -> StdResult<()> {
+        let sender = env.contract.address.to_string();
+        let res = translate_response(res, |msg| match msg {
+            NeutronMsg::CreateDenom { subdenom } => MsgCreateDenom {
+                sender: sender.clone(),
+                subdenom: subdenom.clone(),
+            }
+            .into(),
+            NeutronMsg::MintTokens {
+                denom,
+                amount,
+                mint_to_address,
+            } => MsgMint {
+                sender: sender.clone(),
+                amount: Some(coin_msg(denom, *amount)),
+                mint_to_address: mint_to_address.clone(),
+            }
+            .into(),
+            NeutronMsg::BurnTokens {
+                denom,
+                amount,
+                burn_from_address,
+            } => MsgBurn {
+                sender: sender.clone(),
+                amount: Some(coin_msg(denom, *amount)),
+                burn_from_address: burn_from_address.clone(),
+            }
+            .into(),
+        });
+        deps.querier.apply_response(env, &res)
+    }
+}
+
+/// [`VaultTokenHarness`] for [`KujiraDenom`] with the crate's [`KujiraMsg`]
+/// as custom message type. The messages are applied to the ledger as the
+/// equivalent Osmosis token factory messages.
+#[derive(Default)]
+pub struct KujiraDenomHarness;
+
+impl VaultTokenHarness for KujiraDenomHarness {
+    type CustomMsg = KujiraMsg;
+    type CustomQuery = Empty;
+    type Token = KujiraDenom;
+
+    fn setup(&self) -> (MockTokenDeps, Env, KujiraDenom) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let token = KujiraDenom::new(env.contract.address.to_string(), "vault".to_string());
+
+        let res: Response<KujiraMsg> = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        self.apply_response(&mut deps, &env, &res).unwrap();
+
+        (deps, env, token)
+    }
+
+    fn apply_response(
+        &self,
+        deps: &mut MockTokenDeps,
+        env: &Env,
+        res: &Response<KujiraMsg>,
+    ) -> StdResult<()> {
+        let sender = env.contract.address.to_string();
+        let res = translate_response(res, |KujiraMsg::Denom(msg)| match msg {
+            DenomMsg::Create { subdenom } => MsgCreateDenom {
+                sender: sender.clone(),
+                subdenom: subdenom.clone(),
+            }
+            .into(),
+            DenomMsg::Mint {
+                denom,
+                amount,
+                recipient,
+            } => MsgMint {
+                sender: sender.clone(),
+                amount: Some(coin_msg(denom, *amount)),
+                mint_to_address: recipient.to_string(),
+            }
+            .into(),
+            DenomMsg::Burn { denom, amount } => MsgBurn {
+                sender: sender.clone(),
+                amount: Some(coin_msg(denom, *amount)),
+                burn_from_address: String::new(),
+            }
+            .into(),
+        });
+        deps.querier.apply_response(env, &res)
+    }
+}
+
+/// [`VaultTokenHarness`] for [`crate::injective::InjectiveDenom`]. The
+/// Injective token factory messages are applied to the ledger as the
+/// equivalent Osmosis token factory messages.
+#[cfg(feature = "injective")]
+#[derive(Default)]
+pub struct InjectiveDenomHarness;
+
+#[cfg(feature = "injective")]
+impl VaultTokenHarness for InjectiveDenomHarness {
+    type CustomMsg = Empty;
+    type CustomQuery = Empty;
+    type Token = crate::injective::InjectiveDenom;
+
+    fn setup(&self) -> (MockTokenDeps, Env, Self::Token) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let token = crate::injective::InjectiveDenom::new(
+            env.contract.address.to_string(),
+            "vault".to_string(),
+        );
+
+        let res: Response = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        self.apply_response(&mut deps, &env, &res).unwrap();
+
+        (deps, env, token)
+    }
+
+    fn apply_response(&self, deps: &mut MockTokenDeps, env: &Env, res: &Response) -> StdResult<()> {
+        use crate::injective;
+        use prost::Message;
+
+        let messages = res
+            .messages
+            .iter()
+            .map(|sub_msg| match &sub_msg.msg {
+                CosmosMsg::Stargate { type_url, value } => Ok(match type_url.as_str() {
+                    injective::MsgCreateDenom::TYPE_URL => {
+                        let msg = injective::MsgCreateDenom::decode(value.as_slice())?;
+                        MsgCreateDenom {
+                            sender: msg.sender,
+                            subdenom: msg.subdenom,
+                        }
+                        .into()
+                    }
+                    // Injective mints to the sender
+                    injective::MsgMint::TYPE_URL => {
+                        let msg = injective::MsgMint::decode(value.as_slice())?;
+                        MsgMint {
+                            mint_to_address: msg.sender.clone(),
+                            sender: msg.sender,
+                            amount: msg.amount,
+                        }
+                        .into()
+                    }
+                    injective::MsgBurn::TYPE_URL => {
+                        let msg = injective::MsgBurn::decode(value.as_slice())?;
+                        MsgBurn {
+                            sender: msg.sender,
+                            amount: msg.amount,
+                            burn_from_address: String::new(),
+                        }
+                        .into()
+                    }
+                    _ => sub_msg.msg.clone(),
+                }),
+                msg => Ok(msg.clone()),
+            })
+            .collect::<Result<Vec<CosmosMsg>, prost::DecodeError>>()
+            .map_err(|err| cosmwasm_std::StdError::parse_err("injective message", err))?;
+        deps.querier
+            .apply_response(env, &Response::new().add_messages(messages))
+    }
+}
+
+/// ## Description
+/// Converts a response with custom messages of type `C` into one that
+/// [`MockTokenFactoryQuerier::apply_response`] understands, for use in
+/// [`VaultTokenHarness::apply_response`]. Custom messages are converted with
+/// `translate`, bank and stargate messages are kept as is.
+///
+/// ## Panics
+/// Panics if the response contains any other message.
+pub fn translate_response<C: CustomMsg>(
+    res: &Response<C>,
+    translate: impl Fn(&C) -> CosmosMsg,
+) -> Response {
+    let messages: Vec<CosmosMsg> = res
+        .messages
+        .iter()
+        .map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Custom(msg) => translate(msg),
+            CosmosMsg::Bank(msg) => CosmosMsg::Bank(msg.clone()),
+            CosmosMsg::Stargate { type_url, value } => CosmosMsg::Stargate {
+                type_url: type_url.clone(),
+                value: value.clone(),
+            },
+            msg => panic!("unsupported message {msg:?}"),
+        })
+        .collect();
+    Response::new().add_messages(messages)
+}
+
+/// ## Description
+/// Runs all conformance checks against the implementation set up by
+/// `harness`. Every check starts from a fresh [`VaultTokenHarness::setup`].
+///
+/// ## Panics
+/// Panics if any of the checks fail.
+///
+/// ## Example
+/// ```ignore
+/// #[test]
+/// fn my_token_conforms() {
+///     run_conformance_suite(&MyTokenHarness);
+///     run_conformance_suite(&OsmosisDenomHarness::<MyMsg, MyQuery>::default());
+/// }
+/// ```
+pub fn run_conformance_suite<H: VaultTokenHarness>(harness: &H) {
+    check_mint(harness);
+    check_mint_zero_amount(harness);
+    check_burn(harness);
+    check_burn_zero_amount(harness);
+    check_burn_exceeding_balance(harness);
+    check_transfer(harness);
+    check_transfer_zero_amount(harness);
+    check_receive(harness);
+    check_receive_insufficient(harness);
+    check_receive_zero_amount(harness);
+    check_receive_wrong_denom(harness);
+}
+
+/// Minting increases the recipient's balance and the total supply.
+pub fn check_mint<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+
+    let res: CwTokenResponse<H::CustomMsg> =
+        token.mint(deps.as_mut(), &env, &user, Uint128::new(1000));
+    let res = apply(harness, &mut deps, &env, res).expect("mint failed");
+
+    assert_event(&res, "mint", Uint128::new(1000));
+    assert_supply(harness, &deps, &token, 1000);
+    assert_balance(&deps, &token, &user, 1000);
+}
+
+/// Minting zero tokens is rejected with [`CwTokenError::ZeroAmount`].
+pub fn check_mint_zero_amount<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+
+    let res: CwTokenResponse<H::CustomMsg> =
+        token.mint(deps.as_mut(), &env, &Addr::unchecked(USER), Uint128::zero());
+    assert_zero_amount(res, "mint");
+    assert_supply(harness, &deps, &token, 0);
+}
+
+/// Burning decreases the contract's balance and the total supply.
+pub fn check_burn<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    mint(
+        harness,
+        &mut deps,
+        &env,
+        &token,
+        &env.contract.address,
+        1000,
+    );
+
+    let res: CwTokenResponse<H::CustomMsg> = token.burn(deps.as_mut(), &env, Uint128::new(400));
+    let res = apply(harness, &mut deps, &env, res).expect("burn failed");
+
+    assert_event(&res, "burn", Uint128::new(400));
+    assert_supply(harness, &deps, &token, 600);
+    assert_balance(&deps, &token, &env.contract.address, 600);
+}
+
+/// Burning zero tokens is rejected with [`CwTokenError::ZeroAmount`].
+pub fn check_burn_zero_amount<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    mint(
+        harness,
+        &mut deps,
+        &env,
+        &token,
+        &env.contract.address,
+        1000,
+    );
+
+    let res: CwTokenResponse<H::CustomMsg> = token.burn(deps.as_mut(), &env, Uint128::zero());
+    assert_zero_amount(res, "burn");
+    assert_supply(harness, &deps, &token, 1000);
+}
+
+/// Burning more than the contract's balance fails, either when called or when
+/// the response is executed.
+pub fn check_burn_exceeding_balance<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    mint(harness, &mut deps, &env, &token, &env.contract.address, 100);
+
+    let res: CwTokenResponse<H::CustomMsg> = token.burn(deps.as_mut(), &env, Uint128::new(101));
+    assert!(
+        apply(harness, &mut deps, &env, res).is_err(),
+        "burning more than the balance should fail"
+    );
+    assert_supply(harness, &deps, &token, 100);
+}
+
+/// Transferring moves tokens from the contract to the recipient without
+/// changing the total supply.
+pub fn check_transfer<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+    mint(
+        harness,
+        &mut deps,
+        &env,
+        &token,
+        &env.contract.address,
+        1000,
+    );
+
+    let res: CwTokenResponse<H::CustomMsg> =
+        token.transfer(deps.as_mut(), &env, &user, Uint128::new(300));
+    let res = apply(harness, &mut deps, &env, res).expect("transfer failed");
+
+    assert_event(&res, "transfer", Uint128::new(300));
+    assert_supply(harness, &deps, &token, 1000);
+    assert_balance(&deps, &token, &env.contract.address, 700);
+    assert_balance(&deps, &token, &user, 300);
+}
+
+/// Transferring zero tokens is rejected with [`CwTokenError::ZeroAmount`].
+pub fn check_transfer_zero_amount<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    mint(
+        harness,
+        &mut deps,
+        &env,
+        &token,
+        &env.contract.address,
+        1000,
+    );
+
+    let res: CwTokenResponse<H::CustomMsg> =
+        token.transfer(deps.as_mut(), &env, &Addr::unchecked(USER), Uint128::zero());
+    assert_zero_amount(res, "transfer");
+}
+
+/// Receiving moves tokens from the sender to the contract without changing
+/// the total supply.
+pub fn check_receive<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+    mint(harness, &mut deps, &env, &token, &user, 1000);
+
+    let info = harness.receive_info(&mut deps, &env, &token, &user, Uint128::new(400));
+    token
+        .receive(deps.as_mut(), &env, &info, Uint128::new(400))
+        .expect("receive failed");
+
+    assert_supply(harness, &deps, &token, 1000);
+    assert_balance(&deps, &token, &env.contract.address, 400);
+    assert_balance(&deps, &token, &user, 600);
+}
+
+/// Receiving more tokens than were sent fails.
+pub fn check_receive_insufficient<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+    mint(harness, &mut deps, &env, &token, &user, 100);
+
+    let info = harness.receive_info(&mut deps, &env, &token, &user, Uint128::new(100));
+    assert!(
+        token
+            .receive(deps.as_mut(), &env, &info, Uint128::new(101))
+            .is_err(),
+        "receiving more than was sent should fail"
+    );
+}
+
+/// Receiving zero tokens is rejected with [`CwTokenError::ZeroAmount`].
+pub fn check_receive_zero_amount<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+    mint(harness, &mut deps, &env, &token, &user, 100);
+
+    let info = harness.receive_info(&mut deps, &env, &token, &user, Uint128::zero());
+    match token.receive(deps.as_mut(), &env, &info, Uint128::zero()) {
+        Err(CwTokenError::ZeroAmount {}) => {}
+        res => panic!("receive of zero amount should fail with ZeroAmount, got {res:?}"),
+    }
+}
+
+/// Receiving fails if another denom than the token was sent. Skipped for
+/// tokens without a denom, see [`VaultTokenHarness::wrong_denom_info`].
+pub fn check_receive_wrong_denom<H: VaultTokenHarness>(harness: &H) {
+    let (mut deps, env, token) = harness.setup();
+    let user = Addr::unchecked(USER);
+    mint(harness, &mut deps, &env, &token, &user, 100);
+
+    let info = match harness.wrong_denom_info(&mut deps, &env, &token, &user, Uint128::new(100)) {
+        Some(info) => info,
+        None => return,
+    };
+    assert!(
+        token
+            .receive(deps.as_mut(), &env, &info, Uint128::new(100))
+            .is_err(),
+        "receiving with another denom sent should fail"
+    );
+    assert_balance(&deps, &token, &user, 100);
+}
+
+/// Applies the response of a trait call to the ledger, returning an error if
+/// either the call or executing its messages failed.
+fn apply<H: VaultTokenHarness>(
+    harness: &H,
+    deps: &mut MockTokenDeps<H::CustomQuery>,
+    env: &Env,
+    res: CwTokenResponse<H::CustomMsg>,
+) -> Result<Response<H::CustomMsg>, CwTokenError> {
+    let res = res?;
+    harness.apply_response(deps, env, &res)?;
+    Ok(res)
+}
+
+fn mock_dependencies<Q: CustomQuery>() -> MockTokenDeps<Q> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MockTokenFactoryQuerier::default(),
+        custom_query_type: PhantomData,
+    }
+}
+
+/// Sends `amount` of `denom` from `sender` to the contract in the ledger and
+/// returns the [`MessageInfo`] of the message executed with the funds.
+fn send_funds<Q: CustomQuery>(
+    deps: &mut MockTokenDeps<Q>,
+    env: &Env,
+    sender: &Addr,
+    denom: String,
+    amount: Uint128,
+) -> MessageInfo {
+    let funds = vec![Coin { denom, amount }];
+    if !amount.is_zero() {
+        deps.querier
+            .apply_msg::<()>(
+                sender,
+                &BankMsg::Send {
+                    to_address: env.contract.address.to_string(),
+                    amount: funds.clone(),
+                }
+                .into(),
+            )
+            .unwrap();
+    }
+    MessageInfo {
+        sender: sender.clone(),
+        funds,
+    }
+}
+
+fn mint<H: VaultTokenHarness>(
+    harness: &H,
+    deps: &mut MockTokenDeps<H::CustomQuery>,
+    env: &Env,
+    token: &H::Token,
+    to: &Addr,
+    amount: u128,
+) {
+    let res: CwTokenResponse<H::CustomMsg> =
+        token.mint(deps.as_mut(), env, to, Uint128::new(amount));
+    apply(harness, deps, env, res).expect("mint failed");
+}
+
+fn assert_supply<H: VaultTokenHarness>(
+    harness: &H,
+    deps: &MockTokenDeps<H::CustomQuery>,
+    token: &H::Token,
+    expected: u128,
+) {
+    assert_eq!(
+        harness.total_supply(deps, token),
+        Uint128::new(expected),
+        "unexpected total supply"
+    );
+}
+
+/// Returns the protobuf coin of `amount` tokens of `denom`.
+fn coin_msg(denom: &str, amount: Uint128) -> CoinMsg {
+    CoinMsg {
+        denom: denom.to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+fn assert_balance<Q: CustomQuery, T: Query<Q>>(
+    deps: &MockTokenDeps<Q>,
+    token: &T,
+    address: &Addr,
+    expected: u128,
+) {
+    assert_eq!(
+        token.query_balance(deps.as_ref(), address).unwrap(),
+        Uint128::new(expected),
+        "unexpected balance of {address}"
+    );
+}
+
+fn assert_zero_amount<C>(res: CwTokenResponse<C>, action: &str) {
+    match res {
+        Err(CwTokenError::ZeroAmount {}) => {}
+        Err(err) => panic!("{action} of zero amount should fail with ZeroAmount, got {err:?}"),
+        Ok(_) => panic!("{action} of zero amount should fail with ZeroAmount"),
+    }
+}
+
+/// Asserts that `res` contains an `apollo/cw-vault-token/*` event with the
+/// given `action` and `amount` attributes.
+fn assert_event<C>(res: &Response<C>, action: &str, amount: Uint128) {
+    let found = res.events.iter().any(|event| {
+        event.ty.starts_with("apollo/cw-vault-token/")
+            && event
+                .attributes
+                .iter()
+                .any(|a| a.key == "action" && a.value == action)
+            && event
+                .attributes
+                .iter()
+                .any(|a| a.key == "amount" && a.value == amount.to_string())
+    });
+    assert!(
+        found,
+        "no {action} event with amount {amount} in {:?}",
+        res.events
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_schema::cw_serde;
+
+    use super::*;
+
+    #[cw_serde]
+    struct TestMsg {}
+
+    impl CustomMsg for TestMsg {}
+
+    #[cw_serde]
+    struct TestQuery {}
+
+    impl CustomQuery for TestQuery {}
+
+    #[test]
+    fn test_osmosis_denom_conforms() {
+        run_conformance_suite(&OsmosisDenomHarness::new());
+    }
+
+    #[test]
+    fn test_cw4626_conforms() {
+        run_conformance_suite(&Cw4626Harness::new());
+    }
+
+    #[test]
+    fn test_neutron_denom_conforms() {
+        run_conformance_suite(&NeutronDenomHarness);
+    }
+
+    #[test]
+    fn test_kujira_denom_conforms() {
+        run_conformance_suite(&KujiraDenomHarness);
+    }
+
+    #[cfg(feature = "injective")]
+    #[test]
+    fn test_injective_denom_conforms() {
+        run_conformance_suite(&InjectiveDenomHarness);
+    }
+
+    #[test]
+    fn test_custom_types_conform() {
+        run_conformance_suite(&OsmosisDenomHarness::<TestMsg, TestQuery>::default());
+        run_conformance_suite(&Cw4626Harness::<TestMsg, TestQuery>::default());
+    }
+}
@@ -0,0 +1,12 @@
+/// Conformance test suite for vault token implementations
+mod conformance;
+
+/// Mock querier with an in-memory bank and token factory ledger
+mod querier;
+
+/// Test robot for running vault tokens against a test-tube runner
+mod robot;
+
+pub use conformance::*;
+pub use querier::*;
+pub use robot::*;
@@ -0,0 +1,820 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_slice, to_binary, Addr, AllBalanceResponse, BalanceResponse, BankMsg, BankQuery, Binary,
+    Coin, CosmosMsg, Empty, Env, OwnedDeps, Querier, QuerierResult, QueryRequest, Response,
+    StdError, StdResult, SystemError, SystemResult, Uint128,
+};
+use osmosis_std::types::cosmos::bank::v1beta1::{
+    Metadata, QueryDenomMetadataRequest, QueryDenomMetadataResponse, QuerySupplyOfRequest,
+    QuerySupplyOfResponse,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as CoinMsg;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
+    DenomAuthorityMetadata, MsgBurn, MsgChangeAdmin, MsgCreateDenom, MsgMint, MsgSetBeforeSendHook,
+    MsgSetDenomMetadata, QueryBeforeSendHookAddressRequest, QueryBeforeSendHookAddressResponse,
+    QueryDenomAuthorityMetadataRequest, QueryDenomAuthorityMetadataResponse,
+    QueryDenomsFromCreatorRequest, QueryDenomsFromCreatorResponse,
+};
+
+const SUPPLY_OF_PATH: &str = "/cosmos.bank.v1beta1.Query/SupplyOf";
+const DENOM_METADATA_PATH: &str = "/cosmos.bank.v1beta1.Query/DenomMetadata";
+const DENOM_AUTHORITY_METADATA_PATH: &str =
+    "/osmosis.tokenfactory.v1beta1.Query/DenomAuthorityMetadata";
+const DENOMS_FROM_CREATOR_PATH: &str = "/osmosis.tokenfactory.v1beta1.Query/DenomsFromCreator";
+const BEFORE_SEND_HOOK_ADDRESS_PATH: &str =
+    "/osmosis.tokenfactory.v1beta1.Query/BeforeSendHookAddress";
+
+/// Creates [`OwnedDeps`] with a [`MockTokenFactoryQuerier`], for unit testing
+/// contracts using [`crate::osmosis::OsmosisDenom`] without a chain.
+pub fn mock_dependencies_with_token_factory(
+) -> OwnedDeps<MockStorage, MockApi, MockTokenFactoryQuerier, Empty> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MockTokenFactoryQuerier::default(),
+        custom_query_type: PhantomData,
+    }
+}
+
+/// An extension of [`MockQuerier`] that keeps an in-memory ledger of bank
+/// balances and token factory denoms. Bank balance and supply queries, the stargate
+/// `SupplyOf` and `DenomMetadata` queries and the token factory
+/// `DenomAuthorityMetadata`, `DenomsFromCreator` and `BeforeSendHookAddress`
+/// queries are answered from the ledger. All other queries are forwarded to `base`.
+///
+/// The ledger is updated by applying the messages of a [`Response`] with
+/// [`MockTokenFactoryQuerier::apply_response`], which executes the
+/// `MsgCreateDenom`, `MsgMint`, `MsgBurn`, `MsgChangeAdmin`,
+/// `MsgSetDenomMetadata`, `MsgSetBeforeSendHook`, `BankMsg::Send` and
+/// `BankMsg::Burn` messages in it the way the chain would. Before send hooks
+/// are recorded, but not called.
+///
+/// ## Example
+/// ```ignore
+/// let mut deps = mock_dependencies_with_token_factory();
+/// let env = mock_env();
+/// let token = OsmosisDenom::new(env.contract.address.to_string(), "vault".to_string());
+///
+/// let res = token.instantiate(deps.as_mut(), Default::default()).unwrap();
+/// deps.querier.apply_response(&env, &res).unwrap();
+/// let res = token.mint(deps.as_mut(), &env, &user, Uint128::new(100)).unwrap();
+/// deps.querier.apply_response(&env, &res).unwrap();
+///
+/// assert_eq!(token.query_total_supply(deps.as_ref()).unwrap(), Uint128::new(100));
+/// ```
+#[derive(Default)]
+pub struct MockTokenFactoryQuerier {
+    /// The querier used for all queries not answered from the ledger.
+    pub base: MockQuerier,
+    /// The state of the bank and token factory modules.
+    ledger: Ledger,
+}
+
+/// The state of the bank and token factory modules kept by
+/// [`MockTokenFactoryQuerier`]. Cloned before applying messages, so that
+/// failed messages can be reverted like a failed transaction.
+#[derive(Clone, Default)]
+struct Ledger {
+    /// The admin of each token factory denom.
+    denom_admins: BTreeMap<String, String>,
+    /// The bank balances of each address, by denom.
+    balances: BTreeMap<String, BTreeMap<String, Uint128>>,
+    /// The bank metadata of each denom, if it has been set.
+    denom_metadata: BTreeMap<String, Metadata>,
+    /// The before send hook contract of each denom, if it has been set.
+    before_send_hooks: BTreeMap<String, String>,
+}
+
+impl MockTokenFactoryQuerier {
+    /// Creates a new querier with the given initial bank balances.
+    pub fn new(balances: &[(&str, &[Coin])]) -> Self {
+        let mut querier = Self::default();
+        for (address, coins) in balances {
+            for coin in coins.iter() {
+                querier.set_balance(*address, coin.clone());
+            }
+        }
+        querier
+    }
+
+    /// Sets the balance of `address` in `coin.denom` to `coin.amount`.
+    pub fn set_balance(&mut self, address: impl Into<String>, coin: Coin) {
+        self.ledger
+            .balances
+            .entry(address.into())
+            .or_default()
+            .insert(coin.denom, coin.amount);
+    }
+
+    /// Returns the balance of `address` in `denom`.
+    pub fn balance(&self, address: &str, denom: &str) -> Uint128 {
+        self.ledger
+            .balances
+            .get(address)
+            .and_then(|balances| balances.get(denom))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the total supply of `denom`, i.e. the sum of all balances.
+    pub fn supply(&self, denom: &str) -> Uint128 {
+        self.ledger
+            .balances
+            .values()
+            .filter_map(|balances| balances.get(denom))
+            .sum()
+    }
+
+    /// Returns the admin of the token factory `denom`, if it has been created.
+    pub fn denom_admin(&self, denom: &str) -> Option<&str> {
+        self.ledger.denom_admins.get(denom).map(String::as_str)
+    }
+
+    /// Returns the bank metadata of `denom`, if it has been set.
+    pub fn denom_metadata(&self, denom: &str) -> Option<&Metadata> {
+        self.ledger.denom_metadata.get(denom)
+    }
+
+    /// ## Description
+    /// Applies all messages in `res` to the ledger, as if they were sent by
+    /// the contract `env.contract.address`.
+    ///
+    /// The messages are applied atomically, like the messages of a
+    /// transaction: if any of them fails, the ledger is left unchanged.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if any of the messages would fail on chain. See
+    /// [`MockTokenFactoryQuerier::apply_msg`].
+    pub fn apply_response<T>(&mut self, env: &Env, res: &Response<T>) -> StdResult<()> {
+        self.atomically(|querier| {
+            res.messages
+                .iter()
+                .try_for_each(|sub_msg| querier.execute_msg(&env.contract.address, &sub_msg.msg))
+        })
+    }
+
+    /// ## Description
+    /// Applies a single message sent by `sender` to the ledger. Messages other
+    /// than `MsgCreateDenom`, `MsgMint`, `MsgBurn`, `MsgChangeAdmin`,
+    /// `MsgSetDenomMetadata`, `MsgSetBeforeSendHook`, `BankMsg::Send` and
+    /// `BankMsg::Burn` are ignored.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if the message can't be decoded, if its signer is
+    /// not `sender`, if a denom is created twice, if a denom is minted or
+    /// burned by someone other than its admin, or if a balance is
+    /// insufficient. The ledger is left unchanged on error.
+    pub fn apply_msg<T>(&mut self, sender: &Addr, msg: &CosmosMsg<T>) -> StdResult<()> {
+        self.atomically(|querier| querier.execute_msg(sender, msg))
+    }
+
+    /// Runs `f` on the querier, restoring the ledger if it fails.
+    fn atomically(&mut self, f: impl FnOnce(&mut Self) -> StdResult<()>) -> StdResult<()> {
+        let ledger = self.ledger.clone();
+        let res = f(self);
+        if res.is_err() {
+            self.ledger = ledger;
+        }
+        res
+    }
+
+    /// Executes a single message sent by `sender`, see
+    /// [`MockTokenFactoryQuerier::apply_msg`]. Changes made before an error
+    /// are not reverted.
+    fn execute_msg<T>(&mut self, sender: &Addr, msg: &CosmosMsg<T>) -> StdResult<()> {
+        match msg {
+            CosmosMsg::Stargate { type_url, value } => match type_url.as_str() {
+                MsgCreateDenom::TYPE_URL => {
+                    let msg = MsgCreateDenom::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    let denom = format!("factory/{}/{}", msg.sender, msg.subdenom);
+                    if self.ledger.denom_admins.contains_key(&denom) {
+                        return Err(StdError::generic_err(format!(
+                            "denom {denom} already exists"
+                        )));
+                    }
+                    self.ledger.denom_admins.insert(denom, msg.sender);
+                    Ok(())
+                }
+                MsgMint::TYPE_URL => {
+                    let msg = MsgMint::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    let coin = self.authorized_coin(&msg.sender, msg.amount)?;
+                    self.add_balance(&msg.mint_to_address, &coin)
+                }
+                MsgBurn::TYPE_URL => {
+                    let msg = MsgBurn::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    let coin = self.authorized_coin(&msg.sender, msg.amount)?;
+                    let burn_from = match msg.burn_from_address.is_empty() {
+                        true => msg.sender,
+                        false => msg.burn_from_address,
+                    };
+                    self.sub_balance(&burn_from, &coin)
+                }
+                MsgChangeAdmin::TYPE_URL => {
+                    let msg = MsgChangeAdmin::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    self.authorize(&msg.sender, &msg.denom)?;
+                    self.ledger.denom_admins.insert(msg.denom, msg.new_admin);
+                    Ok(())
+                }
+                MsgSetBeforeSendHook::TYPE_URL => {
+                    let msg = MsgSetBeforeSendHook::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    self.authorize(&msg.sender, &msg.denom)?;
+                    match msg.cosmwasm_address.is_empty() {
+                        true => self.ledger.before_send_hooks.remove(&msg.denom),
+                        false => self
+                            .ledger
+                            .before_send_hooks
+                            .insert(msg.denom, msg.cosmwasm_address),
+                    };
+                    Ok(())
+                }
+                MsgSetDenomMetadata::TYPE_URL => {
+                    let msg = MsgSetDenomMetadata::try_from(value.clone())?;
+                    assert_signer(sender, &msg.sender)?;
+                    let metadata = msg
+                        .metadata
+                        .ok_or_else(|| StdError::generic_err("metadata is required"))?;
+                    self.authorize(&msg.sender, &metadata.base)?;
+                    self.ledger
+                        .denom_metadata
+                        .insert(metadata.base.clone(), metadata);
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                amount.iter().try_for_each(|coin| {
+                    self.sub_balance(sender.as_str(), coin)?;
+                    self.add_balance(to_address, coin)
+                })
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => amount
+                .iter()
+                .try_for_each(|coin| self.sub_balance(sender.as_str(), coin)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Parses the coin of a `MsgMint` or `MsgBurn` and checks that `sender` is
+    /// the admin of its denom.
+    fn authorized_coin(&self, sender: &str, amount: Option<CoinMsg>) -> StdResult<Coin> {
+        let amount = amount.ok_or_else(|| StdError::generic_err("amount is required"))?;
+        self.authorize(sender, &amount.denom)?;
+        Ok(Coin {
+            amount: Uint128::from_str(&amount.amount)?,
+            denom: amount.denom,
+        })
+    }
+
+    /// Checks that `sender` is the admin of the token factory `denom`.
+    fn authorize(&self, sender: &str, denom: &str) -> StdResult<()> {
+        match self.denom_admin(denom) {
+            Some(admin) if admin == sender => Ok(()),
+            Some(_) => Err(StdError::generic_err(format!(
+                "unauthorized account: {sender} is not the admin of {denom}"
+            ))),
+            None => Err(StdError::not_found(format!("denom {denom}"))),
+        }
+    }
+
+    fn add_balance(&mut self, address: &str, coin: &Coin) -> StdResult<()> {
+        let balance = self
+            .balance(address, &coin.denom)
+            .checked_add(coin.amount)?;
+        self.set_balance(address, Coin::new(balance.u128(), &coin.denom));
+        Ok(())
+    }
+
+    fn sub_balance(&mut self, address: &str, coin: &Coin) -> StdResult<()> {
+        let balance = self
+            .balance(address, &coin.denom)
+            .checked_sub(coin.amount)
+            .map_err(|_| {
+                StdError::generic_err(format!("insufficient funds: {address} can't spend {coin}"))
+            })?;
+        self.set_balance(address, Coin::new(balance.u128(), &coin.denom));
+        Ok(())
+    }
+
+    fn query_bank(&self, query: &BankQuery) -> StdResult<Binary> {
+        match query {
+            BankQuery::Balance { address, denom } => to_binary(&BalanceResponse {
+                amount: Coin::new(self.balance(address, denom).u128(), denom),
+            }),
+            BankQuery::AllBalances { address } => to_binary(&AllBalanceResponse {
+                amount: self
+                    .ledger
+                    .balances
+                    .get(address)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, amount)| !amount.is_zero())
+                    .map(|(denom, amount)| Coin::new(amount.u128(), denom))
+                    .collect(),
+            }),
+            // `SupplyResponse` can't be constructed outside of cosmwasm-std,
+            // but serializes exactly like `BalanceResponse`
+            #[cfg(feature = "cosmwasm_1_1")]
+            BankQuery::Supply { denom } => to_binary(&BalanceResponse {
+                amount: Coin::new(self.supply(denom).u128(), denom),
+            }),
+            _ => Err(StdError::generic_err("unsupported bank query")),
+        }
+    }
+
+    fn query_stargate(&self, path: &str, data: &Binary) -> StdResult<Binary> {
+        match path {
+            SUPPLY_OF_PATH => {
+                let req = QuerySupplyOfRequest::try_from(data.clone())?;
+                to_binary(&QuerySupplyOfResponse {
+                    amount: Some(CoinMsg {
+                        amount: self.supply(&req.denom).to_string(),
+                        denom: req.denom,
+                    }),
+                })
+            }
+            DENOM_METADATA_PATH => {
+                let req = QueryDenomMetadataRequest::try_from(data.clone())?;
+                let metadata = self.denom_metadata(&req.denom).ok_or_else(|| {
+                    StdError::not_found(format!("client metadata for denom {}", req.denom))
+                })?;
+                to_binary(&QueryDenomMetadataResponse {
+                    metadata: Some(metadata.clone()),
+                })
+            }
+            DENOM_AUTHORITY_METADATA_PATH => {
+                let req = QueryDenomAuthorityMetadataRequest::try_from(data.clone())?;
+                let admin = self
+                    .denom_admin(&req.denom)
+                    .ok_or_else(|| StdError::not_found(format!("denom {}", req.denom)))?;
+                to_binary(&QueryDenomAuthorityMetadataResponse {
+                    authority_metadata: Some(DenomAuthorityMetadata {
+                        admin: admin.to_string(),
+                    }),
+                })
+            }
+            BEFORE_SEND_HOOK_ADDRESS_PATH => {
+                let req = QueryBeforeSendHookAddressRequest::try_from(data.clone())?;
+                to_binary(&QueryBeforeSendHookAddressResponse {
+                    cosmwasm_address: self
+                        .ledger
+                        .before_send_hooks
+                        .get(&req.denom)
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+            }
+            DENOMS_FROM_CREATOR_PATH => {
+                let req = QueryDenomsFromCreatorRequest::try_from(data.clone())?;
+                let prefix = format!("factory/{}/", req.creator);
+                to_binary(&QueryDenomsFromCreatorResponse {
+                    denoms: self
+                        .ledger
+                        .denom_admins
+                        .keys()
+                        .filter(|denom| denom.starts_with(&prefix))
+                        .cloned()
+                        .collect(),
+                })
+            }
+            _ => Err(StdError::generic_err("unsupported stargate query")),
+        }
+    }
+}
+
+/// Checks that `signer`, the sender encoded in a stargate message, is the
+/// account sending it. The chain rejects messages not signed by their sender.
+fn assert_signer(sender: &Addr, signer: &str) -> StdResult<()> {
+    match sender.as_str() == signer {
+        true => Ok(()),
+        false => Err(StdError::generic_err(format!(
+            "unauthorized: {sender} can't send a message signed by {signer}"
+        ))),
+    }
+}
+
+impl Querier for MockTokenFactoryQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {e}"),
+                    request: bin_request.into(),
+                })
+            }
+        };
+
+        let res = match &request {
+            QueryRequest::Bank(query) => self.query_bank(query),
+            QueryRequest::Stargate { path, data } => match path.as_str() {
+                SUPPLY_OF_PATH
+                | DENOM_METADATA_PATH
+                | DENOM_AUTHORITY_METADATA_PATH
+                | DENOMS_FROM_CREATOR_PATH
+                | BEFORE_SEND_HOOK_ADDRESS_PATH => self.query_stargate(path, data),
+                _ => return self.base.handle_query(&request),
+            },
+            _ => return self.base.handle_query(&request),
+        };
+        SystemResult::Ok(res.map_err(|e| e.to_string()).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::mock_env;
+    use osmosis_std::types::osmosis::tokenfactory::v1beta1::TokenfactoryQuerier;
+
+    use crate::osmosis::{OsmosisDenom, OsmosisDenomInstantiateMsg};
+    use crate::{Burn, BurnFrom, CwTokenError, Instantiate, Mint, Query, Transfer};
+    use osmosis_std::types::cosmos::bank::v1beta1::DenomUnit;
+
+    use super::*;
+
+    const USER: &str = "user";
+
+    fn setup() -> (
+        OwnedDeps<MockStorage, MockApi, MockTokenFactoryQuerier>,
+        Env,
+        OsmosisDenom,
+    ) {
+        let mut deps = mock_dependencies_with_token_factory();
+        let env = mock_env();
+        let token = OsmosisDenom::new(env.contract.address.to_string(), "vault".to_string());
+
+        let res: Response = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+
+        (deps, env, token)
+    }
+
+    #[test]
+    fn test_create_denom() {
+        let (mut deps, env, token) = setup();
+
+        let deps_ref = deps.as_ref();
+        let querier = TokenfactoryQuerier::new(&deps_ref.querier);
+        let admin = querier
+            .denom_authority_metadata(token.to_string())
+            .unwrap()
+            .authority_metadata
+            .unwrap()
+            .admin;
+        assert_eq!(admin, env.contract.address.to_string());
+        let denoms = querier
+            .denoms_from_creator(env.contract.address.to_string())
+            .unwrap()
+            .denoms;
+        assert_eq!(denoms, vec![token.to_string()]);
+
+        // Creating the same denom twice fails
+        let res: Response = token
+            .instantiate(deps.as_mut(), Default::default())
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap_err();
+    }
+
+    #[test]
+    fn test_mint_burn_and_transfer() {
+        let (mut deps, env, token) = setup();
+        let user = Addr::unchecked(USER);
+
+        let res: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        let res: Response = token
+            .transfer(deps.as_mut(), &env, &user, Uint128::new(400))
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        let res: Response = token.burn(deps.as_mut(), &env, Uint128::new(100)).unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        let res: Response = token
+            .burn_from(deps.as_mut(), &env, &user, Uint128::new(150))
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+
+        assert_eq!(
+            token.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::new(750)
+        );
+        assert_eq!(
+            token
+                .query_balance(deps.as_ref(), &env.contract.address)
+                .unwrap(),
+            Uint128::new(500)
+        );
+        assert_eq!(
+            token.query_balance(deps.as_ref(), USER).unwrap(),
+            Uint128::new(250)
+        );
+
+        // Burning more than the balance fails
+        let res: Response = token
+            .burn_from(deps.as_mut(), &env, &user, Uint128::new(251))
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap_err();
+    }
+
+    #[cfg(feature = "cosmwasm_1_1")]
+    #[test]
+    fn test_bank_supply_backend() {
+        let (mut deps, env, token) = setup();
+        let token = token.with_supply_backend(crate::SupplyBackend::Bank);
+
+        let res: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &Addr::unchecked(USER),
+                Uint128::new(1000),
+            )
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        let res: Response = token
+            .burn_from(
+                deps.as_mut(),
+                &env,
+                &Addr::unchecked(USER),
+                Uint128::new(300),
+            )
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+
+        assert_eq!(
+            token.query_total_supply(deps.as_ref()).unwrap(),
+            Uint128::new(700)
+        );
+        assert_eq!(
+            deps.as_ref().querier.query_supply("uosmo").unwrap(),
+            Coin::new(0, "uosmo")
+        );
+    }
+
+    #[test]
+    fn test_failed_response_is_reverted() {
+        let (mut deps, env, token) = setup();
+        let user = Addr::unchecked(USER);
+
+        // The mint succeeds, but the transfer of more than was minted fails
+        let res: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        let transfer: Response = token
+            .transfer(deps.as_mut(), &env, &user, Uint128::new(1001))
+            .unwrap();
+        let res = res.add_submessages(transfer.messages);
+        deps.querier.apply_response(&env, &res).unwrap_err();
+
+        assert_eq!(deps.querier.supply(&token.to_string()), Uint128::zero());
+        assert_eq!(
+            deps.querier
+                .balance(env.contract.address.as_str(), &token.to_string()),
+            Uint128::zero()
+        );
+        assert_eq!(
+            deps.querier.balance(USER, &token.to_string()),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn test_signer_must_be_sender() {
+        let (mut deps, env, token) = setup();
+
+        // A message signed by the admin but sent by another contract fails
+        let res: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        let err = deps
+            .querier
+            .apply_msg(&Addr::unchecked("other_contract"), &res.messages[0].msg)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(format!(
+                "unauthorized: other_contract can't send a message signed by {}",
+                env.contract.address
+            ))
+        );
+        assert_eq!(deps.querier.supply(&token.to_string()), Uint128::zero());
+    }
+
+    #[test]
+    fn test_only_admin_can_mint() {
+        let (mut deps, _, token) = setup();
+        let mut env = mock_env();
+        env.contract.address = Addr::unchecked("other_contract");
+
+        let res: Response = token
+            .mint(
+                deps.as_mut(),
+                &env,
+                &env.contract.address,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap_err();
+        assert_eq!(deps.querier.supply(&token.to_string()), Uint128::zero());
+    }
+
+    #[test]
+    fn test_denom_metadata() {
+        let mut deps = mock_dependencies_with_token_factory();
+        let env = mock_env();
+        let token = OsmosisDenom::new(env.contract.address.to_string(), "vault".to_string());
+        let metadata = Metadata {
+            description: "Vault token".to_string(),
+            denom_units: vec![
+                DenomUnit {
+                    denom: token.to_string(),
+                    exponent: 0,
+                    aliases: vec![],
+                },
+                DenomUnit {
+                    denom: "vault".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ],
+            base: String::new(),
+            display: "vault".to_string(),
+            name: "Vault".to_string(),
+            symbol: "VAULT".to_string(),
+        };
+
+        // Querying before the metadata is set fails
+        token.query_metadata(deps.as_ref()).unwrap_err();
+
+        let init_info = OsmosisDenomInstantiateMsg {
+            inflation_protection: None,
+            metadata: Some(metadata.clone()),
+        };
+        let res: Response = token.instantiate(deps.as_mut(), init_info).unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        assert_eq!(
+            token.query_metadata(deps.as_ref()).unwrap(),
+            Metadata {
+                base: token.to_string(),
+                ..metadata.clone()
+            }
+        );
+
+        let updated = Metadata {
+            symbol: "vVAULT".to_string(),
+            ..metadata
+        };
+        let res: Response = token
+            .set_metadata(deps.as_ref(), &env, updated.clone())
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        assert_eq!(
+            token.query_metadata(deps.as_ref()).unwrap().symbol,
+            "vVAULT"
+        );
+
+        // Only the admin can set the metadata
+        let mut other_env = mock_env();
+        other_env.contract.address = Addr::unchecked("other_contract");
+        let res: Response = token
+            .set_metadata(deps.as_ref(), &other_env, updated.clone())
+            .unwrap();
+        deps.querier.apply_response(&other_env, &res).unwrap_err();
+        let err = token
+            .clone()
+            .with_admin_check(true)
+            .set_metadata::<Empty, _>(deps.as_ref(), &other_env, updated)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            CwTokenError::NotDenomAdmin {
+                denom: token.to_string(),
+                address: "other_contract".to_string(),
+                admin: Some(env.contract.address.to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_admin() {
+        let (mut deps, env, token) = setup();
+        let token = token.with_admin_check(true);
+        let new_admin = Addr::unchecked("new_vault");
+
+        let res: Response = token.change_admin(deps.as_ref(), &env, &new_admin).unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        assert_eq!(
+            token.query_authority_metadata(deps.as_ref()).unwrap().admin,
+            new_admin.to_string()
+        );
+
+        // The old admin can no longer mint, burn or change the admin
+        let expected = CwTokenError::NotDenomAdmin {
+            denom: token.to_string(),
+            address: env.contract.address.to_string(),
+            admin: Some(new_admin.to_string()),
+        };
+        assert_eq!(
+            expected.to_string(),
+            format!(
+                "{} is not the admin of {}, the admin is new_vault",
+                env.contract.address, token
+            )
+        );
+        let err = Mint::<Empty>::mint(&token, deps.as_mut(), &env, &new_admin, Uint128::new(1))
+            .unwrap_err();
+        assert_eq!(err, expected);
+        let err = Burn::<Empty>::burn(&token, deps.as_mut(), &env, Uint128::new(1)).unwrap_err();
+        assert_eq!(err, expected);
+        let err = token
+            .change_admin::<Empty, _>(deps.as_ref(), &env, &env.contract.address)
+            .unwrap_err();
+        assert_eq!(err, expected);
+
+        // The new admin can mint
+        let mut new_env = mock_env();
+        new_env.contract.address = new_admin.clone();
+        let res: Response = token
+            .mint(deps.as_mut(), &new_env, &new_admin, Uint128::new(1000))
+            .unwrap();
+        deps.querier.apply_response(&new_env, &res).unwrap();
+        assert_eq!(
+            token.query_balance(deps.as_ref(), &new_admin).unwrap(),
+            Uint128::new(1000)
+        );
+
+        // Once the admin is renounced, the error renders the missing admin
+        let res: Response = token
+            .change_admin(deps.as_ref(), &new_env, &Addr::unchecked(""))
+            .unwrap();
+        deps.querier.apply_response(&new_env, &res).unwrap();
+        let err =
+            Burn::<Empty>::burn(&token, deps.as_mut(), &new_env, Uint128::new(1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("new_vault is not the admin of {token}, the admin is none")
+        );
+    }
+
+    #[test]
+    fn test_before_send_hook() {
+        let (mut deps, env, token) = setup();
+        let hook = Addr::unchecked("hook_contract");
+
+        assert_eq!(token.query_before_send_hook(deps.as_ref()).unwrap(), None);
+
+        let res: Response = token
+            .set_before_send_hook(deps.as_ref(), &env, Some(&hook))
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        assert_eq!(
+            token.query_before_send_hook(deps.as_ref()).unwrap(),
+            Some(hook)
+        );
+
+        let res: Response = token
+            .set_before_send_hook(deps.as_ref(), &env, None)
+            .unwrap();
+        deps.querier.apply_response(&env, &res).unwrap();
+        assert_eq!(token.query_before_send_hook(deps.as_ref()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_other_queries_use_base() {
+        let mut deps = mock_dependencies_with_token_factory();
+        deps.querier = MockTokenFactoryQuerier::new(&[(USER, &[Coin::new(100, "uosmo")])]);
+
+        let balance = deps.as_ref().querier.query_balance(USER, "uosmo").unwrap();
+        assert_eq!(balance, Coin::new(100, "uosmo"));
+        deps.as_ref()
+            .querier
+            .query_wasm_smart::<Empty>("contract", &Empty {})
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,372 @@
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    to_binary, Addr, Attribute, Coin, CosmosMsg, Env, Event, OwnedDeps, Response, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use cw_it::osmosis_std::types::cosmos::bank::v1beta1::{
+    QuerySupplyOfRequest, QuerySupplyOfResponse,
+};
+use cw_it::robot::TestRobot;
+use cw_it::test_tube::{Account, Runner, SigningAccount};
+
+use crate::VaultToken;
+
+/// How a [`TokenRobot`] executes the [`VaultToken`] methods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenRobotMode {
+    /// The methods are called in the robot with mock dependencies, using the
+    /// signer's address as the contract address, and the messages of the
+    /// returned [`Response`] are executed by the signer. Used for native
+    /// tokens such as [`crate::osmosis::OsmosisDenom`], whose balances are
+    /// queried from the bank module. The mock storage is kept for the
+    /// lifetime of the robot, so state written by the token, e.g. the dead
+    /// shares of [`crate::InflationProtection`], persists between calls.
+    Messages,
+    /// The methods are called inside the wasm contract at `contract`, by
+    /// executing a [`TokenRobotExecuteMsg`] on it. Used for tokens that keep
+    /// their state in the contract such as [`crate::cw4626::Cw4626`], whose
+    /// balances are queried with [`Cw20QueryMsg`]. A minimal contract for
+    /// [`crate::cw4626::Cw4626`] is provided in `test-contracts/cw4626-robot`.
+    Contract {
+        /// Address of the contract holding the token
+        contract: String,
+    },
+}
+
+#[cw_serde]
+/// Execute messages a contract must accept to be tested with
+/// [`TokenRobotMode::Contract`]. Each variant should call the [`VaultToken`]
+/// method of the same name with `env.contract.address` as the token's
+/// contract, and return its [`Response`].
+pub enum TokenRobotExecuteMsg {
+    /// Calls [`crate::Mint::mint`]
+    Mint {
+        /// The recipient of the minted tokens
+        recipient: String,
+        /// The amount to mint
+        amount: Uint128,
+    },
+    /// Calls [`crate::Burn::burn`]
+    Burn {
+        /// The amount to burn
+        amount: Uint128,
+    },
+    /// Calls [`crate::Transfer::transfer`]
+    Transfer {
+        /// The recipient of the tokens
+        recipient: String,
+        /// The amount to transfer
+        amount: Uint128,
+    },
+    /// Calls [`crate::Receive::receive`] with the sender and funds of the
+    /// message
+    Receive {
+        /// The amount to receive
+        amount: Uint128,
+    },
+}
+
+/// A [`TestRobot`] for [`VaultToken`] implementations. Every method executes
+/// the matching trait method on the runner, records the emitted events and
+/// panics on failure, so calls can be chained and asserted on.
+///
+/// ## Example
+/// ```ignore
+/// let denom = OsmosisDenom::new(creator.address(), "subdenom".to_string());
+///
+/// TokenRobot::new(&app, &denom)
+///     .instantiate::<MsgCreateDenomResponse>(creator, Default::default())
+///     .mint::<MsgMintResponse>(creator, &recipient.address(), amount)
+///     .assert_balance_eq(recipient.address(), amount)
+///     .assert_total_supply_eq(amount);
+/// ```
+pub struct TokenRobot<'a, R: Runner<'a>, T: VaultToken> {
+    app: &'a R,
+    token: &'a T,
+    mode: TokenRobotMode,
+    /// The mock dependencies of [`TokenRobotMode::Messages`]
+    deps: OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    /// The events emitted by the last executed transaction
+    pub last_events: Vec<Event>,
+}
+
+impl<'a, R, T> TestRobot<'a, R> for TokenRobot<'a, R, T>
+where
+    R: Runner<'a> + 'a,
+    T: VaultToken,
+{
+    fn runner(&self) -> &'a R {
+        self.app
+    }
+}
+
+impl<'a, R, T> TokenRobot<'a, R, T>
+where
+    R: Runner<'a> + 'a,
+    T: VaultToken,
+{
+    /// Creates a new robot using [`TokenRobotMode::Messages`].
+    pub fn new(app: &'a R, token: &'a T) -> Self {
+        Self::with_mode(app, token, TokenRobotMode::Messages)
+    }
+
+    /// Creates a new robot using [`TokenRobotMode::Contract`] with the
+    /// contract at `contract`.
+    pub fn new_contract(app: &'a R, token: &'a T, contract: impl Into<String>) -> Self {
+        Self::with_mode(
+            app,
+            token,
+            TokenRobotMode::Contract {
+                contract: contract.into(),
+            },
+        )
+    }
+
+    /// Creates a new robot using the given [`TokenRobotMode`].
+    pub fn with_mode(app: &'a R, token: &'a T, mode: TokenRobotMode) -> Self {
+        Self {
+            app,
+            token,
+            mode,
+            deps: mock_dependencies(),
+            last_events: vec![],
+        }
+    }
+
+    /// Instantiates the token by executing the messages of
+    /// [`crate::Instantiate::instantiate`]. Only supported in
+    /// [`TokenRobotMode::Messages`], since in [`TokenRobotMode::Contract`] the
+    /// token is instantiated by the contract's own instantiate entry point.
+    pub fn instantiate<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        init_info: T::InitInfo,
+    ) -> &mut Self {
+        assert_eq!(
+            self.mode,
+            TokenRobotMode::Messages,
+            "instantiate is only supported in TokenRobotMode::Messages"
+        );
+        let response = self
+            .token
+            .instantiate(self.deps.as_mut(), init_info)
+            .unwrap();
+
+        self.execute_response::<S>(signer, response)
+    }
+
+    /// Mints `amount` tokens to `recipient`.
+    pub fn mint<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        recipient: &str,
+        amount: Uint128,
+    ) -> &mut Self {
+        match self.mode {
+            TokenRobotMode::Messages => {
+                let env = mock_env_with_address(&signer.address());
+                let response = self
+                    .token
+                    .mint(
+                        self.deps.as_mut(),
+                        &env,
+                        &Addr::unchecked(recipient),
+                        amount,
+                    )
+                    .unwrap();
+                self.execute_response::<S>(signer, response)
+            }
+            TokenRobotMode::Contract { .. } => self.execute_contract::<S>(
+                signer,
+                TokenRobotExecuteMsg::Mint {
+                    recipient: recipient.to_string(),
+                    amount,
+                },
+                vec![],
+            ),
+        }
+    }
+
+    /// Burns `amount` tokens from the contract's balance.
+    pub fn burn<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        amount: Uint128,
+    ) -> &mut Self {
+        match self.mode {
+            TokenRobotMode::Messages => {
+                let env = mock_env_with_address(&signer.address());
+                let response = self.token.burn(self.deps.as_mut(), &env, amount).unwrap();
+                self.execute_response::<S>(signer, response)
+            }
+            TokenRobotMode::Contract { .. } => {
+                self.execute_contract::<S>(signer, TokenRobotExecuteMsg::Burn { amount }, vec![])
+            }
+        }
+    }
+
+    /// Transfers `amount` tokens from the contract's balance to `recipient`.
+    pub fn transfer<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        recipient: &str,
+        amount: Uint128,
+    ) -> &mut Self {
+        match self.mode {
+            TokenRobotMode::Messages => {
+                let env = mock_env_with_address(&signer.address());
+                let response = self
+                    .token
+                    .transfer(
+                        self.deps.as_mut(),
+                        &env,
+                        &Addr::unchecked(recipient),
+                        amount,
+                    )
+                    .unwrap();
+                self.execute_response::<S>(signer, response)
+            }
+            TokenRobotMode::Contract { .. } => self.execute_contract::<S>(
+                signer,
+                TokenRobotExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                },
+                vec![],
+            ),
+        }
+    }
+
+    /// Sends `amount` tokens from the signer to the contract, which receives
+    /// them using [`crate::Receive::receive`]. Only supported in
+    /// [`TokenRobotMode::Contract`]. Native tokens are sent as `funds`.
+    pub fn receive<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        amount: Uint128,
+        funds: Vec<Coin>,
+    ) -> &mut Self {
+        self.execute_contract::<S>(signer, TokenRobotExecuteMsg::Receive { amount }, funds)
+    }
+
+    /// Executes the messages of `res` with `signer` and records the emitted
+    /// events.
+    pub fn execute_response<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        res: Response,
+    ) -> &mut Self {
+        let cosmos_msgs: Vec<CosmosMsg> = res.messages.into_iter().map(|x| x.msg).collect();
+
+        let execute_res = self
+            .app
+            .execute_cosmos_msgs::<S>(&cosmos_msgs, signer)
+            .unwrap();
+
+        self.last_events = execute_res.events;
+
+        self
+    }
+
+    /// Executes `msg` on the contract of [`TokenRobotMode::Contract`].
+    fn execute_contract<S: ::prost::Message + Default>(
+        &mut self,
+        signer: &SigningAccount,
+        msg: TokenRobotExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> &mut Self {
+        let contract_addr = match &self.mode {
+            TokenRobotMode::Contract { contract } => contract.clone(),
+            TokenRobotMode::Messages => {
+                panic!("{msg:?} is only supported in TokenRobotMode::Contract")
+            }
+        };
+        let response = Response::new().add_message(WasmMsg::Execute {
+            contract_addr,
+            msg: to_binary(&msg).unwrap(),
+            funds,
+        });
+
+        self.execute_response::<S>(signer, response)
+    }
+
+    /// Returns the token balance of `address`.
+    pub fn query_balance(&self, address: impl Into<String>) -> Uint128 {
+        match &self.mode {
+            TokenRobotMode::Messages => {
+                self.query_native_token_balance(address, self.token.to_string())
+            }
+            TokenRobotMode::Contract { contract } => {
+                let res: BalanceResponse = self
+                    .wasm()
+                    .query(
+                        contract,
+                        &Cw20QueryMsg::Balance {
+                            address: address.into(),
+                        },
+                    )
+                    .unwrap();
+                res.balance
+            }
+        }
+    }
+
+    /// Returns the total supply of the token.
+    pub fn query_total_supply(&self) -> Uint128 {
+        match &self.mode {
+            TokenRobotMode::Messages => {
+                let res: QuerySupplyOfResponse = self
+                    .app
+                    .query(
+                        "/cosmos.bank.v1beta1.Query/SupplyOf",
+                        &QuerySupplyOfRequest {
+                            denom: self.token.to_string(),
+                        },
+                    )
+                    .unwrap();
+                res.amount
+                    .map(|c| Uint128::from_str(&c.amount).unwrap())
+                    .unwrap_or_default()
+            }
+            TokenRobotMode::Contract { contract } => {
+                let res: TokenInfoResponse = self
+                    .wasm()
+                    .query(contract, &Cw20QueryMsg::TokenInfo {})
+                    .unwrap();
+                res.total_supply
+            }
+        }
+    }
+
+    /// Asserts that the token balance of `address` is `expected`.
+    pub fn assert_balance_eq(&self, address: impl Into<String>, expected: Uint128) -> &Self {
+        assert_eq!(self.query_balance(address), expected);
+        self
+    }
+
+    /// Asserts that the total supply of the token is `expected`.
+    pub fn assert_total_supply_eq(&self, expected: Uint128) -> &Self {
+        assert_eq!(self.query_total_supply(), expected);
+        self
+    }
+
+    /// Asserts that the last executed transaction emitted an event of type
+    /// `expected_type` with exactly `expected_attributes`.
+    pub fn assert_event(&self, expected_type: &str, expected_attributes: Vec<Attribute>) -> &Self {
+        let expected_event = &Event::new(expected_type).add_attributes(expected_attributes);
+        match self.last_events.contains(expected_event) {
+            true => self,
+            false => panic!("Event not found. Expected {expected_event:?}"),
+        }
+    }
+}
+
+/// Returns a mock [`Env`] with `address` as the contract address.
+fn mock_env_with_address(address: &str) -> Env {
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked(address);
+    env
+}
@@ -1,40 +1,88 @@
-use cosmwasm_std::{Addr, Binary, Deps, DepsMut, Env, MessageInfo, StdResult, Uint128};
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_std::{
+    from_binary, Addr, Binary, Coin, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env,
+    MessageInfo, StdError, Uint128,
+};
 
 use std::fmt::Display;
 
-use crate::{CwTokenResponse, CwTokenResult};
+use crate::{CwTokenResponse, CwTokenResult, InflationProtection};
 
 /// Combined trait for implementations that can be used as a vault token.
-pub trait VaultToken: Instantiate + Mint + Burn + Receive + Display {
+///
+/// The traits are generic over the custom message type `C` of the returned
+/// responses and the custom query type `Q` of the dependencies, so that
+/// tokens can be used on chains exposing their token factory through custom
+/// bindings. Both default to [`Empty`]. Implementations that only use
+/// standard messages and queries should implement the traits for any `C` and
+/// `Q`.
+///
+/// The balance and total supply queries are provided by the [`Query`]
+/// supertrait, so they can be called on any `T: VaultToken`. Callers using a
+/// concrete implementation must import [`Query`].
+///
+/// This trait is implemented for all types implementing its supertraits.
+pub trait VaultToken<C: CustomMsg = Empty, Q: CustomQuery = Empty>:
+    Instantiate<C, Q> + Mint<C, Q> + Burn<C, Q> + Receive<Q> + Transfer<C, Q> + Query<Q> + Display
+{
+}
+
+impl<T, C, Q> VaultToken<C, Q> for T
+where
+    T: Instantiate<C, Q>
+        + Mint<C, Q>
+        + Burn<C, Q>
+        + Receive<Q>
+        + Transfer<C, Q>
+        + Query<Q>
+        + Display,
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+}
+
+/// A trait encapsulating the behavior necessary for querying balances and
+/// supply. Only generic over the custom query type, so that the queries can
+/// be called without specifying a custom message type.
+pub trait Query<Q: CustomQuery = Empty> {
     /// ## Description
     /// Query the balance of the vault token for `address`.
     ///
     /// ## Errors
     /// May return `CwTokenError`.
-    fn query_balance<A: Into<String>>(&self, deps: Deps, address: A) -> CwTokenResult<Uint128>;
+    fn query_balance<A: Into<String>>(&self, deps: Deps<Q>, address: A) -> CwTokenResult<Uint128>;
 
     /// ## Description
     /// Query the total supply of the vault token.
     ///
     /// ## Errors
     /// May return `CwTokenError`.
-    fn query_total_supply(&self, deps: Deps) -> CwTokenResult<Uint128>;
+    fn query_total_supply(&self, deps: Deps<Q>) -> CwTokenResult<Uint128>;
+
+    /// ## Description
+    /// Query the [`InflationProtection`] configured when the token was
+    /// instantiated. Used by [`crate::VaultTokenMath`].
+    ///
+    /// ## Errors
+    /// May return `CwTokenError`.
+    fn query_inflation_protection(&self, deps: Deps<Q>) -> CwTokenResult<InflationProtection>;
 }
 
 /// A trait encapsulating the behavior necessary for instantiation of a token.
-pub trait Instantiate {
+pub trait Instantiate<C: CustomMsg = Empty, Q: CustomQuery = Empty> {
+    /// The information needed to instantiate the token, e.g. the name and
+    /// symbol of a cw20 token.
+    type InitInfo: Serialize + DeserializeOwned;
+
     /// ## Description
     /// Instantiate a new token. This function should be called in the
     /// `instantiate` entry point of the contract, to instantiate a new
     /// token.
     ///
     /// ## Arguments
-    /// - `init_info`: The information needed to instantiate the token as a
-    ///   Binary. It is up to the implementation to deserialize this and to the
-    ///   caller to serialize a proper struct matching the needs of specific
-    ///   implementation. The reason this is binary is so that we don't need yet
-    ///   another generic argument. It is optional as not all implementations
-    ///   need info to be able to initialize.
+    /// - `init_info`: The information needed to instantiate the token, as
+    ///   defined by the implementation's [`Instantiate::InitInfo`].
     ///
     /// ## Returns
     /// Returns a Response containing the messages to instantiate the token.
@@ -52,14 +100,38 @@ pub trait Instantiate {
     ///     msg: InstantiateMsg,
     /// ) -> Result<Response, ContractError> {
     ///     let my_token = MyToken::new(..);
-    ///     my_token.instantiate(deps, to_binary(&msg.init_info)?)
+    ///     my_token.instantiate(deps, msg.init_info)
     /// }
     /// ```
-    fn instantiate(&self, deps: DepsMut, init_info: Option<Binary>) -> CwTokenResponse;
+    fn instantiate(&self, deps: DepsMut<Q>, init_info: Self::InitInfo) -> CwTokenResponse<C>;
+
+    /// ## Description
+    /// Instantiate a new token from binary encoded init info. This is a shim
+    /// for generic callers that don't know the concrete implementation, and
+    /// therefore can't construct an [`Instantiate::InitInfo`].
+    ///
+    /// ## Arguments
+    /// - `init_info`: The [`Instantiate::InitInfo`] of the implementation,
+    ///   serialized as JSON. Implementations that can be instantiated without
+    ///   any info may accept `None`.
+    ///
+    /// ## Errors
+    /// Returns [`StdError`] if `init_info` is `None` or can't be deserialized
+    /// into [`Instantiate::InitInfo`], and may otherwise return
+    /// [`CwTokenError`].
+    fn instantiate_binary(
+        &self,
+        deps: DepsMut<Q>,
+        init_info: Option<Binary>,
+    ) -> CwTokenResponse<C> {
+        let init_info =
+            from_binary(&init_info.ok_or_else(|| StdError::generic_err("init_info required"))?)?;
+        self.instantiate(deps, init_info)
+    }
 }
 
 /// A trait encapsulating the behavior necessary for Minting
-pub trait Mint {
+pub trait Mint<C: CustomMsg = Empty, Q: CustomQuery = Empty> {
     /// ## Description
     /// Mints `amount` new vault tokens to the `recipient` address.
     /// The contract should validate that the recipient is allowed to do this
@@ -69,21 +141,67 @@ pub trait Mint {
     ///
     /// ## Errors
     /// May return [`CwTokenError`].
-    fn mint(&self, deps: DepsMut, env: &Env, recipient: &Addr, amount: Uint128) -> CwTokenResponse;
+    fn mint(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C>;
 }
 
 /// A trait encapsulating the behavior necessary for Burning
-pub trait Burn {
+pub trait Burn<C: CustomMsg = Empty, Q: CustomQuery = Empty> {
     /// ## Description
     /// Burns vault tokens from the contract's balance.
     ///
     /// ## Errors
     /// May return [`CwTokenError`].
-    fn burn(&self, deps: DepsMut, env: &Env, amount: Uint128) -> CwTokenResponse;
+    fn burn(&self, deps: DepsMut<Q>, env: &Env, amount: Uint128) -> CwTokenResponse<C>;
+}
+
+/// A trait encapsulating the behavior necessary for burning tokens directly
+/// from an owner's balance. This is an opt-in capability that is not part of
+/// [`VaultToken`], since not all implementations can support it.
+pub trait BurnFrom<C: CustomMsg = Empty, Q: CustomQuery = Empty> {
+    /// ## Description
+    /// Burns `amount` vault tokens from the `owner`'s balance, without first
+    /// having to receive them into the contract's balance. The contract
+    /// should validate that the caller is allowed to redeem the owner's
+    /// tokens before calling this function.
+    ///
+    /// ## Errors
+    /// May return [`CwTokenError`].
+    fn burn_from(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        owner: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C>;
+}
+
+/// A trait encapsulating the behavior necessary for Transfer
+pub trait Transfer<C: CustomMsg = Empty, Q: CustomQuery = Empty> {
+    /// ## Description
+    /// Transfers `amount` vault tokens from the contract's balance to the
+    /// `recipient` address. This can be used to e.g. send vault tokens that
+    /// were previously received by the contract to a user, without having to
+    /// know which implementation is used.
+    ///
+    /// ## Errors
+    /// May return [`CwTokenError`].
+    fn transfer(
+        &self,
+        deps: DepsMut<Q>,
+        env: &Env,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> CwTokenResponse<C>;
 }
 
 /// A trait encapsulating the behavior necessary for Receive
-pub trait Receive {
+pub trait Receive<Q: CustomQuery = Empty> {
     /// ## Description
     /// Receive the vault token into the contracts balance, or validate that
     /// they have already been received.
@@ -93,13 +211,21 @@ pub trait Receive {
     /// the beginning of a contract `ExecuteMsg` handler, and then know that
     /// after this the behavior is the same for both for both implementations.
     ///
+    /// ## Returns
+    /// Returns the funds in `info.funds` that were not consumed as vault
+    /// tokens, so that handlers accepting both vault tokens and other assets
+    /// in the same message can account for everything that was sent. Native
+    /// implementations reject a surplus of the vault token itself instead of
+    /// returning it, see [`crate::osmosis::ReceiveMode`].
+    ///
     /// ## Errors
-    /// May return [`CwTokenError`].
+    /// Returns [`crate::CwTokenError::ZeroAmount`] if `amount` is zero. May
+    /// return other [`CwTokenError`]s.
     fn receive(
         &self,
-        deps: DepsMut,
+        deps: DepsMut<Q>,
         env: &Env,
         info: &MessageInfo,
         amount: Uint128,
-    ) -> StdResult<()>;
+    ) -> CwTokenResult<Vec<Coin>>;
 }
@@ -0,0 +1,56 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw20_base::msg::QueryMsg;
+use cw_vault_token::cw4626::{Cw4626, Cw4626InstantiateMsg};
+use cw_vault_token::{Burn, CwTokenResponse, Instantiate, Mint, Receive, Transfer};
+
+#[cw_serde]
+/// Mirrors `cw_vault_token::testing::TokenRobotExecuteMsg`, which is only
+/// available with the `testing` feature and can't be compiled to wasm.
+pub enum ExecuteMsg {
+    /// Mints `amount` tokens to `recipient`
+    Mint { recipient: String, amount: Uint128 },
+    /// Burns `amount` tokens from the contract's balance
+    Burn { amount: Uint128 },
+    /// Transfers `amount` tokens from the contract's balance to `recipient`
+    Transfer { recipient: String, amount: Uint128 },
+    /// Receives `amount` tokens from the sender's balance
+    Receive { amount: Uint128 },
+}
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: Cw4626InstantiateMsg,
+) -> CwTokenResponse {
+    Cw4626::new(&env).instantiate(deps, msg)
+}
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> CwTokenResponse {
+    let token = Cw4626::new(&env);
+    match msg {
+        ExecuteMsg::Mint { recipient, amount } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            token.mint(deps, &env, &recipient, amount)
+        }
+        ExecuteMsg::Burn { amount } => token.burn(deps, &env, amount),
+        ExecuteMsg::Transfer { recipient, amount } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            token.transfer(deps, &env, &recipient, amount)
+        }
+        ExecuteMsg::Receive { amount } => {
+            token.receive(deps, &env, &info, amount)?;
+            Ok(Response::new())
+        }
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    cw20_base::contract::query(deps, env, msg)
+}
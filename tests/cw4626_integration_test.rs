@@ -1,19 +1,28 @@
-use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier};
-use cosmwasm_std::{to_binary, Coin, DepsMut, Empty, MemoryStorage, OwnedDeps, Uint128};
-use cw20_base::state::{TokenInfo, TOKEN_INFO};
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
 
 use cw_vault_token::cw4626::{Cw4626, Cw4626InstantiateMsg};
+use cw_vault_token::testing::TokenRobot;
 
-use cw_vault_token::{Burn, Instantiate, Mint, VaultToken};
-
+use cw_it::osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContractResponse;
 use cw_it::osmosis_test_tube::OsmosisTestApp;
-use cw_it::test_tube::{Account, Runner, SigningAccount};
+use cw_it::test_tube::{Account, Module, SigningAccount, Wasm};
+
+use test_case::test_case;
 
 const SUBDENOM: &str = "subdenom";
 
-#[test]
-/// Runs all tests against the Osmosis bindings
-pub fn test_with_osmosis_bindings() {
+/// The test contract in `test-contracts/cw4626-robot`, built with `cargo make
+/// build-test-contracts`
+const TEST_CONTRACT_WASM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/test-contracts/cw4626-robot/target/wasm32-unknown-unknown/release/cw4626_robot.wasm"
+);
+
+/// Stores and instantiates the test contract, returning its address and the
+/// [`Cw4626`] token it holds
+pub fn setup() -> (OsmosisTestApp, Vec<SigningAccount>, String, Cw4626) {
     let app = OsmosisTestApp::default();
 
     let accs = app
@@ -26,125 +35,120 @@ pub fn test_with_osmosis_bindings() {
         )
         .unwrap();
 
-    let (cw4626, mut deps) = test_instantiate(&app, &accs);
-    test_mint(&app, &accs, &cw4626, deps.as_mut());
-    test_burn(&app, &accs, &cw4626, deps.as_mut());
-    query_vault_supply(&app, &accs, &cw4626, deps.as_mut());
-    query_balance(&app, &accs, &cw4626, deps.as_mut());
+    let wasm = Wasm::new(&app);
+    let wasm_byte_code = std::fs::read(TEST_CONTRACT_WASM)
+        .expect("test contract not found, run `cargo make build-test-contracts`");
+    let code_id = wasm
+        .store_code(&wasm_byte_code, None, &accs[0])
+        .unwrap()
+        .data
+        .code_id;
+    let contract = wasm
+        .instantiate(
+            code_id,
+            &Cw4626InstantiateMsg {
+                name: SUBDENOM.to_string(),
+                symbol: "VAULT".to_string(),
+                decimals: 6,
+                marketing: None,
+                inflation_protection: None,
+                snapshots: false,
+            },
+            None,
+            Some("cw4626"),
+            &[],
+            &accs[0],
+        )
+        .unwrap()
+        .data
+        .address;
+
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked(&contract);
+    let cw4626 = Cw4626::new(&env);
+
+    (app, accs, contract, cw4626)
 }
 
-pub fn test_instantiate<R>(
-    _app: &R,
-    _accs: &[SigningAccount],
-) -> (
-    Cw4626,
-    OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>, Empty>,
-)
-where
-    R: for<'a> Runner<'a>,
-{
-    let mut deps = mock_dependencies();
-    let cw4626 = Cw4626::new(&mock_env());
-    let init_info = to_binary(&Cw4626InstantiateMsg {
-        name: SUBDENOM.to_string(),
-        symbol: "VAULT".to_string(),
-        decimals: 6,
-        marketing: None,
-    })
-    .unwrap();
-
-    cw4626.instantiate(deps.as_mut(), Some(init_info)).unwrap();
-
-    let token_info = TOKEN_INFO.load(&deps.storage).unwrap();
+#[test]
+pub fn instantiate() {
+    let (app, _accs, contract, cw4626) = setup();
 
+    let token_info: TokenInfoResponse = Wasm::new(&app)
+        .query(&contract, &Cw20QueryMsg::TokenInfo {})
+        .unwrap();
     assert_eq!(
         token_info,
-        TokenInfo {
+        TokenInfoResponse {
             name: SUBDENOM.to_string(),
             symbol: "VAULT".to_string(),
             decimals: 6,
             total_supply: Uint128::zero(),
-            mint: None
         }
     );
-
-    (cw4626, deps)
+    TokenRobot::new_contract(&app, &cw4626, contract).assert_total_supply_eq(Uint128::zero());
 }
 
-pub fn test_mint<R>(_app: &R, accs: &[SigningAccount], cw4626: &Cw4626, mut deps: DepsMut)
-where
-    R: for<'a> Runner<'a>,
-{
-    let creator = &accs[0];
-
+#[test_case(Uint128::from(10000000u128) ; "mint")]
+#[test_case(Uint128::zero() => panics ; "zero amount")]
+pub fn mint(amount: Uint128) {
+    let (app, accs, contract, cw4626) = setup();
     let recipient = &accs[1];
 
-    let mut env = mock_env();
-    // The sender in the function is `env.contract.address`, therefore I explicitly
-    // changed it. Otherwise, the message will fail upon address verification.
-    env.contract.address = deps.api.addr_validate(&creator.address()).unwrap();
+    let mut robot = TokenRobot::new_contract(&app, &cw4626, contract);
 
-    let amount_to_mint = Uint128::new(10000000);
+    robot
+        .mint::<MsgExecuteContractResponse>(&accs[0], &recipient.address(), amount)
+        .assert_balance_eq(recipient.address(), amount)
+        .assert_total_supply_eq(amount);
+}
 
-    let recipient = deps.api.addr_validate(&recipient.address()).unwrap();
-    cw4626
-        .mint(deps.branch(), &env, &recipient, amount_to_mint)
-        .unwrap();
+#[test_case(Uint128::from(1000000u128) ; "burn")]
+#[test_case(Uint128::zero() => panics ; "zero amount")]
+#[test_case(Uint128::from(10000001u128) => panics ; "insufficient balance")]
+pub fn burn(amount: Uint128) {
+    let (app, accs, contract, cw4626) = setup();
 
-    let supply = cw4626.query_total_supply(deps.as_ref()).unwrap();
-    assert_eq!(supply, Uint128::new(10000000));
+    let mut robot = TokenRobot::new_contract(&app, &cw4626, contract.clone());
 
-    let balance = cw4626.query_balance(deps.as_ref(), recipient).unwrap();
-    assert_eq!(balance, Uint128::new(10000000));
+    robot
+        .mint::<MsgExecuteContractResponse>(&accs[0], &contract, Uint128::new(10000000))
+        .burn::<MsgExecuteContractResponse>(&accs[0], amount)
+        .assert_balance_eq(&contract, Uint128::new(10000000) - amount)
+        .assert_total_supply_eq(Uint128::new(10000000) - amount);
 }
 
-pub fn test_burn<R>(_app: &R, accs: &[SigningAccount], cw4626: &Cw4626, mut deps: DepsMut)
-where
-    R: for<'a> Runner<'a>,
-{
-    let creator = &accs[1];
-
+#[test]
+pub fn transfer() {
+    let (app, accs, contract, cw4626) = setup();
     let recipient = &accs[1];
 
-    let mut env = mock_env();
-    // The sender in the function is `env.contract.address`, therefore I explicitly
-    // changed it. Otherwise, the message will fail upon address verification
-    env.contract.address = deps.api.addr_validate(&creator.address()).unwrap();
-
-    let amount_to_burn = Uint128::new(1000000);
-
-    cw4626.burn(deps.branch(), &env, amount_to_burn).unwrap();
-
-    let supply = cw4626.query_total_supply(deps.as_ref()).unwrap();
-    assert_eq!(supply, Uint128::new(9000000));
+    let mut robot = TokenRobot::new_contract(&app, &cw4626, contract.clone());
 
-    let balance = cw4626
-        .query_balance(deps.as_ref(), recipient.address())
-        .unwrap();
-    assert_eq!(balance, Uint128::new(9000000));
-}
-
-pub fn query_vault_supply<R>(_app: &R, _accs: &[SigningAccount], cw4626: &Cw4626, deps: DepsMut)
-where
-    R: for<'a> Runner<'a>,
-{
-    let supply = cw4626.query_total_supply(deps.as_ref()).unwrap();
-    // Minted 10000000 twice, burned 1000000 once = (10000000 * 2) - 1000000 =
-    // 19000000
-    assert_eq!(supply, Uint128::new(9000000));
+    robot
+        .mint::<MsgExecuteContractResponse>(&accs[0], &contract, Uint128::new(10000000))
+        .transfer::<MsgExecuteContractResponse>(
+            &accs[0],
+            &recipient.address(),
+            Uint128::new(1000000),
+        )
+        .assert_balance_eq(&contract, Uint128::new(9000000))
+        .assert_balance_eq(recipient.address(), Uint128::new(1000000))
+        .assert_total_supply_eq(Uint128::new(10000000));
 }
 
-pub fn query_balance<R>(_app: &R, accs: &[SigningAccount], cw4626: &Cw4626, deps: DepsMut)
-where
-    R: for<'a> Runner<'a>,
-{
-    let recipient = &accs[1];
+#[test_case(Uint128::from(1000000u128) ; "receive")]
+#[test_case(Uint128::from(10000001u128) => panics ; "insufficient balance")]
+pub fn receive(amount: Uint128) {
+    let (app, accs, contract, cw4626) = setup();
+    let user = &accs[1];
 
-    let balance = cw4626
-        .query_balance(deps.as_ref(), recipient.address())
-        .unwrap();
+    let mut robot = TokenRobot::new_contract(&app, &cw4626, contract.clone());
 
-    // Minted 10000000 twice, burned 1000000 once = (10000000 * 2) - 1000000 =
-    // 19000000
-    assert_eq!(balance, Uint128::new(9000000));
+    robot
+        .mint::<MsgExecuteContractResponse>(&accs[0], &user.address(), Uint128::new(10000000))
+        .receive::<MsgExecuteContractResponse>(user, amount, vec![])
+        .assert_balance_eq(&contract, amount)
+        .assert_balance_eq(user.address(), Uint128::new(10000000) - amount)
+        .assert_total_supply_eq(Uint128::new(10000000));
 }
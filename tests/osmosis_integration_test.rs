@@ -1,15 +1,14 @@
-use cosmwasm_std::testing::{mock_dependencies, mock_env};
-use cosmwasm_std::{attr, Api, Attribute, Coin, CosmosMsg, Deps, Env, Event, Response, Uint128};
+use cosmwasm_std::{attr, Coin, Uint128};
 
 use cw_it::robot::TestRobot;
 use cw_vault_token::osmosis::OsmosisDenom;
-use cw_vault_token::{Burn, Instantiate, Mint, VaultToken};
+use cw_vault_token::testing::TokenRobot;
 
 use cw_it::osmosis_std::types::osmosis::tokenfactory::v1beta1::{
     MsgBurnResponse, MsgCreateDenomResponse, MsgMintResponse,
 };
 use cw_it::osmosis_test_tube::OsmosisTestApp;
-use cw_it::test_tube::{Account, Runner, SigningAccount};
+use cw_it::test_tube::{Account, SigningAccount};
 
 use test_case::test_case;
 
@@ -32,103 +31,6 @@ pub fn setup() -> (OsmosisTestApp, Vec<SigningAccount>) {
     (app, accs)
 }
 
-fn mock_env_with_address(deps: Deps, address: &str) -> Env {
-    let mut env = mock_env();
-    env.contract.address = deps.api.addr_validate(address).unwrap();
-    env
-}
-
-#[derive(Clone, Debug)]
-struct TokenRobot<'a, R: Runner<'a>, T: VaultToken + Clone> {
-    app: &'a R,
-    denom: &'a T,
-    last_events: Vec<Event>,
-}
-
-impl<'a, R> TestRobot<'a, R> for TokenRobot<'a, R, OsmosisDenom>
-where
-    R: Runner<'a>,
-{
-    fn runner(&self) -> &'a R {
-        self.app
-    }
-}
-
-impl<'a, R: Runner<'a>> TokenRobot<'a, R, OsmosisDenom> {
-    pub fn new(app: &'a R, denom: &'a OsmosisDenom) -> Self {
-        Self {
-            app,
-            denom,
-            last_events: vec![],
-        }
-    }
-
-    fn instantiate<S: ::prost::Message + Default>(&mut self, signer: &SigningAccount) -> &mut Self {
-        let response = self
-            .denom
-            .instantiate(mock_dependencies().as_mut(), None)
-            .unwrap();
-
-        self.execute_response::<S>(signer, response)
-    }
-
-    fn mint<S: ::prost::Message + Default>(
-        &mut self,
-        signer: &SigningAccount,
-        recipient: &str,
-        amount: Uint128,
-    ) -> &mut Self {
-        let mut deps = mock_dependencies();
-        let env = mock_env_with_address(deps.as_ref(), &signer.address());
-
-        let recipient = deps.api.addr_validate(recipient).unwrap();
-        let response = self
-            .denom
-            .clone()
-            .mint(deps.as_mut(), &env, &recipient, amount)
-            .unwrap();
-        self.execute_response::<S>(signer, response)
-    }
-
-    fn burn<S: ::prost::Message + Default>(
-        &mut self,
-        signer: &SigningAccount,
-        amount: Uint128,
-    ) -> &mut Self {
-        let mut deps = mock_dependencies();
-        let env = mock_env_with_address(deps.as_ref(), &signer.address());
-
-        let response = self.denom.burn(deps.as_mut(), &env, amount).unwrap();
-
-        self.execute_response::<S>(signer, response)
-    }
-
-    fn execute_response<S: ::prost::Message + Default>(
-        &mut self,
-        signer: &SigningAccount,
-        res: Response,
-    ) -> &mut Self {
-        let cosmos_msgs: Vec<CosmosMsg> = res.messages.into_iter().map(|x| x.msg).collect();
-
-        let execute_res = self
-            .app
-            .execute_cosmos_msgs::<S>(&cosmos_msgs, signer)
-            .unwrap();
-
-        self.last_events = execute_res.events;
-
-        self
-    }
-
-    fn assert_event(&self, expected_type: &str, expected_attributes: Vec<Attribute>) -> &Self {
-        let expected_event = &Event::new(expected_type).add_attributes(expected_attributes);
-        match self.last_events.contains(expected_event) {
-            true => self,
-            false => panic!("Event not found. Expected {:?}", expected_event),
-        }
-    }
-}
-
 #[test_case(0 ; "signer is owner")]
 #[test_case(1 => panics ; "signer is not owner")]
 pub fn instantiate(owner_idx: usize) {
@@ -137,7 +39,7 @@ pub fn instantiate(owner_idx: usize) {
     let owner = &accs[owner_idx];
     let denom = OsmosisDenom::new(owner.address(), SUBDENOM.to_string());
 
-    TokenRobot::new(&app, &denom).instantiate::<MsgCreateDenomResponse>(signer);
+    TokenRobot::new(&app, &denom).instantiate::<MsgCreateDenomResponse>(signer, Default::default());
 }
 
 #[test_case(0, Uint128::from(1000000u128) ; "executed by owner")]
@@ -153,7 +55,7 @@ fn mint(signer_idx: usize, amount: Uint128) {
     let mut robot = TokenRobot::new(&app, &denom);
 
     robot
-        .instantiate::<MsgCreateDenomResponse>(creator)
+        .instantiate::<MsgCreateDenomResponse>(creator, Default::default())
         .mint::<MsgMintResponse>(signer, &recipient.address(), amount)
         // TODO: Disabled because of bug in Osmosis TokenFactory:
         // https://github.com/osmosis-labs/osmosis/issues/6147
@@ -181,7 +83,7 @@ fn burn(signer_idx: usize, recipient_idx: usize, amount: Uint128) {
     let mut robot = TokenRobot::new(&app, &denom);
 
     let recipient_balance_before = robot
-        .instantiate::<MsgCreateDenomResponse>(creator)
+        .instantiate::<MsgCreateDenomResponse>(creator, Default::default())
         .mint::<MsgMintResponse>(creator, &recipient.address(), Uint128::from(1000000u128))
         .query_native_token_balance(recipient.address(), denom.to_string());
 